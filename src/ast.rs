@@ -10,14 +10,37 @@ pub enum ParsedItem {
 
 pub type UseAlias = Option<RcStr>;
 
+/// what kind of symbol a use clause imports
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UseKind {
+    Class,
+    Function,
+    Const,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum UseClause {
-    QualifiedName(Path, UseAlias),
+    QualifiedName(UseKind, Path, UseAlias),
+    /// use prefix=.0\{ clauses=.1 }, the paths of the clauses are relative to the prefix
+    Group(Path, Vec<UseClause>),
+}
+
+/// how a name is written, which determines how it is resolved
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathKind {
+    /// name
+    Unqualified,
+    /// ns\name
+    Qualified,
+    /// \ns\name
+    FullyQualified,
+    /// namespace\name, relative to the current namespace
+    Relative,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Path {
-    pub is_absolute: bool,
+    pub kind: PathKind,
     pub namespace: Option<RcStr>,
     /// mostly something like the trait or class name
     pub identifier: RcStr,
@@ -28,7 +51,7 @@ impl Path {
         Path {
             namespace: None,
             identifier: name,
-            is_absolute: absolute,
+            kind: if absolute { PathKind::FullyQualified } else { PathKind::Unqualified },
         }
     }
 
@@ -36,7 +59,7 @@ impl Path {
         Path {
             namespace: Some(namespace),
             identifier: name,
-            is_absolute: absolute,
+            kind: if absolute { PathKind::FullyQualified } else { PathKind::Qualified },
         }
     }
 }
@@ -74,6 +97,8 @@ pub enum Op {
     Spaceship,
     Sl,
     Sr,
+    /// null coalescing operator, ??
+    Coalesce,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -128,7 +153,7 @@ impl ClassModifiers {
 }
 
 /// the boolean indicates whether the underlying item is static or not
-/// TODO: error validation of duplicate and invalid states in ::new
+/// repeated modifiers and multiple visibilities are rejected by the parser
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MemberModifier {
     Public = 1<<0,
@@ -137,6 +162,8 @@ pub enum MemberModifier {
     Static = 1<<3,
     Abstract = 1<<4,
     Final = 1<<5,
+    /// the `var` spelling of public (for properties)
+    Var = 1<<6,
 }
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct MemberModifiers(u8);
@@ -208,29 +235,42 @@ pub enum Expr_ {
     Isset(Vec<Expr>),
     Empty(Box<Expr>),
     Exit(Option<Box<Expr>>),
+    Print(Box<Expr>),
+    Eval(Box<Expr>),
 
     Include(IncludeTy, Box<Expr>),
     ArrayIdx(Box<Expr>, Vec<Option<Expr>>),
     ObjMember(Box<Expr>, Vec<Expr>),
+    /// nullsafe property fetch `$a?->b`
+    NullsafeObjMember(Box<Expr>, Box<Expr>),
+    /// nullsafe method call `$a?->b(args)`
+    NullsafeMethodCall(Box<Expr>, Box<Expr>, Vec<Argument>),
     StaticMember(Box<Expr>, Vec<Expr>),
-    Call(Box<Expr>, Vec<Expr>),
-    New(Box<Expr>, Vec<Expr>),
-    /// variadic unpack ...
-    Unpack(Box<Expr>),
+    Call(Box<Expr>, Vec<Argument>),
+    New(Box<Expr>, Vec<Argument>),
+    /// new attributes=.0 class(args=.1) extends .2 implements .3 { members=.4 }
+    AnonymousClass(Vec<AttributeGroup>, Vec<Argument>, Option<Path>, Vec<Path>, Vec<Member>),
     UnaryOp(UnaryOp, Box<Expr>),
     BinaryOp(Op, Box<Expr>, Box<Expr>),
     InstanceOf(Box<Expr>, Box<Expr>),
-    Cast(Ty, Box<Expr>),
+    Cast(CastTy, Box<Expr>),
+    /// a backtick command, consisting of the literal and interpolated parts
+    ShellExec(Vec<Expr>),
     Yield(Option<Box<Expr>>),
     /// an anonymous function
     Function(FunctionDecl),
+    /// an arrow function `fn() => expr`
+    ArrowFunction(ArrowFunctionDecl),
+    /// match (subject=.0) { arms=.1 }
+    Match(Box<Expr>, Vec<MatchArm>),
 
     // statements
     Assign(Box<Expr>, Box<Expr>),
     /// compound (binary) assign e.g. $test += 3; which is equal to $test = $test + 3; (Assign, BinaryOp)
     CompoundAssign(Box<Expr>, Op, Box<Expr>),
     AssignRef(Box<Expr>, Box<Expr>),
-    List(Vec<(Option<Expr>, Expr)>),
+    /// destructuring assignment target, skipped elements are None and .1 is whether the short `[]` syntax was used
+    List(Vec<Option<(Option<Expr>, Expr)>>, bool),
 
     /// same as if, just will pass the return-value of either expression to the parent
     /// if .1 (then) is None, the value of .0 (condition) will be used
@@ -254,12 +294,12 @@ pub enum Stmt_ {
     Unset(Vec<Expr>),
 
     /// If (condition=.0) { Block=.1 } else Else_Expr=.2
-    If(Box<Expr>, Block, Block),
-    While(Box<Expr>, Block),
+    If(Box<Expr>, Block, Block, BlockSyntax),
+    While(Box<Expr>, Block, BlockSyntax),
     DoWhile(Block, Box<Expr>),
     /// For(initializer=.0; cond=.1; end_of_loop=.2) statement=.3
-    For(Option<Box<Expr>>, Option<Box<Expr>>, Option<Box<Expr>>, Block),
-    ForEach(Box<Expr>, Option<Box<Expr>>, Box<Expr>, Block),
+    For(Option<Box<Expr>>, Option<Box<Expr>>, Option<Box<Expr>>, Block, BlockSyntax),
+    ForEach(Box<Expr>, Option<Box<Expr>>, Box<Expr>, Block, BlockSyntax),
     /// Try(TryBlock, CatchClauses, FinallyClause)
     Try(Block, Vec<CatchClause>, Option<Block>),
     Throw(Box<Expr>),
@@ -268,7 +308,22 @@ pub enum Stmt_ {
     /// All item-cases for a body will be included in the first-member Vec
     /// so basically we have a mapping from all-cases -> body in .1
     /// TODO: should be desugared into an if-statement
-    Switch(Box<Expr>, Vec<SwitchCase>),
+    Switch(Box<Expr>, Vec<SwitchCase>, BlockSyntax),
+    /// goto label=.0;
+    Goto(RcStr),
+    /// label=.0:
+    Label(RcStr),
+    /// __halt_compiler(); with the remaining raw data of the file
+    HaltCompiler(Rc<Vec<u8>>),
+    /// declare(directives=.0) body=.1, the body is None for the statement form `declare(...);`
+    Declare(Vec<(RcStr, Expr)>, Option<Block>, BlockSyntax),
+}
+
+/// whether a block uses braces or the alternative syntax `: ... end*;`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BlockSyntax {
+    Normal,
+    Alternative,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -280,7 +335,29 @@ pub enum Ty {
     Int,
     Double,
     String,
+    /// only valid as return type
+    Void,
+    Iterable,
     Object(Option<Path>),
+    /// a union of two or more types, e.g. `int|string`
+    Union(Vec<Ty>),
+}
+
+/// the target type of a cast, each spelling of a type is kept
+#[derive(Clone, Debug, PartialEq)]
+pub enum CastTy {
+    Int,
+    Integer,
+    Double,
+    Float,
+    Real,
+    String,
+    Binary,
+    Array,
+    Object,
+    Bool,
+    Boolean,
+    Unset,
 }
 
 /// a builtin (internal) constant, which can be resolved at parse-time
@@ -319,13 +396,38 @@ pub enum TraitUse {
     As(Option<Path>, RcStr, MemberModifiers, Option<RcStr>),
 }
 
+/// an attribute `Name(args)`, the arguments are empty when omitted
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    pub name: Path,
+    pub args: Vec<Argument>,
+}
+
+/// an attribute group `#[A, B(1)]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeGroup(pub Vec<Attribute>);
+
+/// an argument passed to a call, e.g. `$a`, `...$args` or `name: $a`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Argument {
+    /// the parameter name for a named argument
+    pub name: Option<RcStr>,
+    pub value: Expr,
+    /// call-time pass-by-reference `&$a`
+    pub by_ref: bool,
+    /// variadic unpack `...$args`
+    pub unpack: bool,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ParamDefinition {
+    pub attributes: Vec<AttributeGroup>,
     pub name: RcStr,
     pub as_ref: bool,
     pub variadic: bool,
     /// The type of the parameter
-    pub ty: Option<Ty>,
+    pub ty: Option<NullableTy>,
     /// The default value for the parameter
     pub default: Option<Expr>,
 }
@@ -339,10 +441,29 @@ pub struct FunctionDecl {
     /// the boolean indicates whether to bind by-reference (true)
     pub usev: Vec<(bool, RcStr)>,
     pub ret_ref: bool,
+    /// whether it's a static closure, which doesn't bind $this
+    pub is_static: bool,
+    pub attributes: Vec<AttributeGroup>,
+    /// The declared return type
+    pub ret_ty: Option<NullableTy>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrowFunctionDecl {
+    pub params: Vec<ParamDefinition>,
+    /// the returned expression
+    pub body: Box<Expr>,
+    pub ret_ref: bool,
+    pub is_static: bool,
+    pub attributes: Vec<AttributeGroup>,
+    pub ret_ty: Option<NullableTy>,
+    /// variables of the parent scope used within the body, which are implicitly captured by-value
+    pub captures: Vec<RcStr>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ClassDecl {
+    pub attributes: Vec<AttributeGroup>,
     pub cmod: ClassModifiers,
     pub name: RcStr,
     pub base_class: Option<Path>,
@@ -351,28 +472,47 @@ pub struct ClassDecl {
     pub members: Vec<Member>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumDecl {
+    pub attributes: Vec<AttributeGroup>,
+    pub name: RcStr,
+    /// The backing type of a backed enum (`enum Suit: string`), which is int or string
+    pub backing_ty: Option<Ty>,
+    /// The implemented interfaces of this enum
+    pub implements: Vec<Path>,
+    pub members: Vec<Member>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Member {
-    Constant(MemberModifiers, RcStr, Expr),
-    Property(MemberModifiers, RcStr, Option<Expr>),
+    Constant(Vec<AttributeGroup>, MemberModifiers, RcStr, Expr),
+    /// case name=.1 = value=.2; (the value is only present in backed enums)
+    EnumCase(Vec<AttributeGroup>, RcStr, Option<Expr>),
+    Property(Vec<AttributeGroup>, MemberModifiers, Option<NullableTy>, RcStr, Option<Expr>),
     Method(MemberModifiers, RcStr, FunctionDecl),
     TraitUse(Vec<Path>, Vec<TraitUse>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Decl {
-    Namespace(Path),
+    /// namespace name=.0 with its statements=.1, .2 indicates whether the braced syntax was used
+    /// the name is None for the global namespace (only possible using the braced syntax)
+    Namespace(Option<Path>, Block, bool),
     GlobalFunction(RcStr, FunctionDecl),
+    /// const name = value, ...;
+    GlobalConstants(Vec<(RcStr, Expr)>),
     Class(ClassDecl),
-    Interface(RcStr, Vec<Path>, Vec<Member>),
-    Trait(RcStr, Vec<Member>),
+    Interface(Vec<AttributeGroup>, RcStr, Vec<Path>, Vec<Member>),
+    Trait(Vec<AttributeGroup>, RcStr, Vec<Member>),
+    Enum(EnumDecl),
     StaticVars(Vec<(RcStr, Option<Expr>)>),
     GlobalVars(Vec<Variable>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CatchClause {
-    pub ty: Path,
+    /// The caught exception types (multiple when using `A | B`)
+    pub types: Vec<Path>,
     pub var: RcStr,
     pub block: Block,
 }
@@ -383,3 +523,11 @@ pub struct SwitchCase {
     pub default: bool,
     pub block: Block,
 }
+
+/// an arm of a match expression, `conds => body` or `default => body`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    pub conds: Vec<Expr>,
+    pub default: bool,
+    pub body: Expr,
+}
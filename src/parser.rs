@@ -8,14 +8,15 @@
 /// ! [3] https://github.com/php/php-src/blob/ab304579ff046426f281e9a95abea8d611e38e1c/Zend/zend_language_parser.y
 
 use std::mem;
+use std::rc::Rc;
 use std::borrow::{Borrow, Cow};
 use std::iter;
 use tokenizer::{Tokenizer, Token, TokenSpan};
 use interner::{Interner, RcStr};
 pub use tokenizer::{Span, SyntaxError, TokenizerExternalState, mk_span};
-pub use ast::{Block, CatchClause, Expr, Expr_, IncludeTy, UnaryOp, Op, Path, SwitchCase, Stmt,
-              Stmt_, NullableTy, Ty, TraitUse, UseClause};
-pub use ast::{Const, Decl, FunctionDecl, ClassDecl, ParamDefinition, Member, MemberModifier,
+pub use ast::{Argument, Attribute, AttributeGroup, Block, BlockSyntax, CastTy, CatchClause, Expr, Expr_, IncludeTy, MatchArm, UnaryOp, Op, Path, PathKind, SwitchCase, Stmt,
+              Stmt_, NullableTy, Ty, TraitUse, UseClause, UseKind};
+pub use ast::{Const, Decl, FunctionDecl, ArrowFunctionDecl, ClassDecl, EnumDecl, ParamDefinition, Member, MemberModifier,
               MemberModifiers, ClassModifier, ClassModifiers};
 pub use ast::Variable;
 
@@ -48,6 +49,15 @@ impl ParserError {
             syntax: Some(e),
         }
     }
+
+    fn message(message: &'static str, position: usize) -> ParserError {
+        ParserError {
+            tokens: vec![],
+            pos: position,
+            message: Some(message),
+            syntax: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -61,10 +71,18 @@ pub struct SpannedParserError {
 }
 
 impl SpannedParserError {
+    /// the byte range of the source the error refers to
+    pub fn span(&self) -> Span {
+        mk_span(self.start, self.end)
+    }
+
     pub fn error_message(&self, code: Option<&str>) -> Cow<'static, str> {
         if let Some(message) = self.error.message {
             return message.into();
         }
+        if let Some(SyntaxError::Invalid(message, _)) = self.error.syntax {
+            return message.into();
+        }
 
         let mut str_ = format!("expected one of {:?} at line {:?}\n", self.error.tokens,
             self.line,
@@ -112,6 +130,224 @@ impl Parser {
     }
 }
 
+/// collect the names of all variables used within an expression (without nested function scopes) in order of occurrence
+fn collect_variables(expr: &Expr, vars: &mut Vec<RcStr>) {
+    fn add(vars: &mut Vec<RcStr>, name: &RcStr) {
+        if !vars.contains(name) {
+            vars.push(name.clone());
+        }
+    }
+    match expr.0 {
+        Expr_::Variable(Variable::Name(ref name)) => add(vars, name),
+        Expr_::Variable(Variable::Fetch(ref expr)) |
+        Expr_::Reference(ref expr) |
+        Expr_::Clone(ref expr) |
+        Expr_::Empty(ref expr) |
+        Expr_::Print(ref expr) |
+        Expr_::Eval(ref expr) |
+        Expr_::Include(_, ref expr) |
+        Expr_::UnaryOp(_, ref expr) |
+        Expr_::Cast(_, ref expr) => collect_variables(expr, vars),
+        Expr_::Exit(Some(ref expr)) |
+        Expr_::Yield(Some(ref expr)) => collect_variables(expr, vars),
+        Expr_::Isset(ref exprs) |
+        Expr_::ShellExec(ref exprs) => for expr in exprs {
+            collect_variables(expr, vars);
+        },
+        Expr_::AnonymousClass(_, ref args, _, _, _) => for arg in args {
+            collect_variables(&arg.value, vars);
+        },
+        Expr_::Array(ref pairs) => for &(ref key, ref value) in pairs {
+            if let Some(ref key) = *key {
+                collect_variables(key, vars);
+            }
+            collect_variables(value, vars);
+        },
+        Expr_::List(ref pairs, _) => for &(ref key, ref value) in pairs.iter().filter_map(Option::as_ref) {
+            if let Some(ref key) = *key {
+                collect_variables(key, vars);
+            }
+            collect_variables(value, vars);
+        },
+        Expr_::ArrayIdx(ref base, ref idxs) => {
+            collect_variables(base, vars);
+            for idx in idxs.iter().filter_map(Option::as_ref) {
+                collect_variables(idx, vars);
+            }
+        }
+        Expr_::ObjMember(ref base, ref args) => {
+            collect_variables(base, vars);
+            for arg in args {
+                collect_variables(arg, vars);
+            }
+        }
+        Expr_::StaticMember(ref base, ref args) => {
+            collect_variables(base, vars);
+            // `A::$s` names a static property, only dynamic members (`A::$$x`) contain variables
+            for arg in args {
+                match arg.0 {
+                    Expr_::Variable(Variable::Name(_)) => (),
+                    _ => collect_variables(arg, vars),
+                }
+            }
+        }
+        Expr_::Call(ref base, ref args) |
+        Expr_::New(ref base, ref args) => {
+            collect_variables(base, vars);
+            for arg in args {
+                collect_variables(&arg.value, vars);
+            }
+        }
+        Expr_::Match(ref subject, ref arms) => {
+            collect_variables(subject, vars);
+            for arm in arms {
+                for cond in &arm.conds {
+                    collect_variables(cond, vars);
+                }
+                collect_variables(&arm.body, vars);
+            }
+        }
+        Expr_::NullsafeObjMember(ref base, ref prop) => {
+            collect_variables(base, vars);
+            collect_variables(prop, vars);
+        }
+        Expr_::NullsafeMethodCall(ref base, ref method, ref args) => {
+            collect_variables(base, vars);
+            collect_variables(method, vars);
+            for arg in args {
+                collect_variables(&arg.value, vars);
+            }
+        }
+        Expr_::BinaryOp(_, ref a, ref b) |
+        Expr_::InstanceOf(ref a, ref b) |
+        Expr_::Assign(ref a, ref b) |
+        Expr_::CompoundAssign(ref a, _, ref b) |
+        Expr_::AssignRef(ref a, ref b) => {
+            collect_variables(a, vars);
+            collect_variables(b, vars);
+        }
+        Expr_::TernaryIf(ref cond, ref case_true, ref case_else) => {
+            collect_variables(cond, vars);
+            if let Some(ref case_true) = *case_true {
+                collect_variables(case_true, vars);
+            }
+            collect_variables(case_else, vars);
+        }
+        // nested functions only access the parent scope through their bindings
+        Expr_::Function(ref decl) => for &(_, ref name) in &decl.usev {
+            add(vars, name);
+        },
+        Expr_::ArrowFunction(ref decl) => for name in &decl.captures {
+            add(vars, name);
+        },
+        Expr_::Path(_) | Expr_::Constant(_) | Expr_::String(_) | Expr_::BinaryString(_) | Expr_::Int(_) | Expr_::Double(_) |
+        Expr_::Exit(None) | Expr_::Yield(None) => (),
+    }
+}
+
+/// a list needs at least one element which isn't skipped, this also applies to nested lists
+fn check_destructuring(expr: &Expr, pos: usize) -> Result<(), ParserError> {
+    if let Expr_::List(ref pairs, _) = expr.0 {
+        if pairs.iter().all(Option::is_none) {
+            return Err(ParserError::message("Cannot use empty list", pos));
+        }
+        for &(_, ref value) in pairs.iter().filter_map(Option::as_ref) {
+            try!(check_destructuring(value, pos));
+        }
+    }
+    Ok(())
+}
+
+/// convert the elements of a short array (or directly nested ones) which turned out not to be a destructuring
+fn array_pairs_from_list(pairs: Vec<Option<(Option<Expr>, Expr)>>, pos: usize) -> Result<Vec<(Option<Expr>, Expr)>, ParserError> {
+    pairs.into_iter().map(|pair| {
+        let (key, value) = try!(pair.ok_or_else(|| ParserError::message("Cannot use empty array elements in arrays", pos)));
+        let value = match value {
+            Expr(Expr_::List(_, false), _) => return Err(ParserError::message("Cannot use list() as standalone expression", pos)),
+            value => try!(array_from_short_array(value, pos)),
+        };
+        Ok((key, value))
+    }).collect()
+}
+
+/// convert a short array which is still undecided (see parse_dereferencable_scalar) into an array
+fn array_from_short_array(expr: Expr, pos: usize) -> Result<Expr, ParserError> {
+    match expr {
+        Expr(Expr_::List(pairs, true), span) => Ok(Expr(Expr_::Array(try!(array_pairs_from_list(pairs, pos))), span)),
+        expr => Ok(expr),
+    }
+}
+
+/// check that a write target doesn't contain a nullsafe access, e.g. `$a?->b = 1`
+fn check_write_context(expr: &Expr, pos: usize) -> Result<(), ParserError> {
+    check_nullsafe_chain(expr, "Can't use nullsafe operator in write context", pos)
+}
+
+/// check that a value which is taken by-reference isn't a nullsafe chain, e.g. `$b = &$a?->b`
+fn check_reference_context(expr: &Expr, pos: usize) -> Result<(), ParserError> {
+    check_nullsafe_chain(expr, "Cannot take reference of a nullsafe chain", pos)
+}
+
+fn check_nullsafe_chain(expr: &Expr, message: &'static str, pos: usize) -> Result<(), ParserError> {
+    match expr.0 {
+        Expr_::NullsafeObjMember(_, _) |
+        Expr_::NullsafeMethodCall(_, _, _) => Err(ParserError::syntax(SyntaxError::Invalid(message, expr.1.clone()), pos)),
+        Expr_::ArrayIdx(ref base, _) |
+        Expr_::ObjMember(ref base, _) |
+        Expr_::StaticMember(ref base, _) |
+        Expr_::Call(ref base, _) => check_nullsafe_chain(base, message, pos),
+        Expr_::List(ref pairs, _) => {
+            for &(_, ref value) in pairs.iter().filter_map(Option::as_ref) {
+                try!(check_nullsafe_chain(value, message, pos));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// collect all labels and gotos of a scope, each with the path of loops/switches (identified by their start) enclosing it
+fn collect_goto_labels(stmts: &[Stmt], path: &mut Vec<u32>, labels: &mut Vec<(RcStr, Vec<u32>)>, gotos: &mut Vec<(RcStr, Vec<u32>, Span)>) {
+    for stmt in stmts {
+        match stmt.0 {
+            Stmt_::Label(ref name) => labels.push((name.clone(), path.clone())),
+            Stmt_::Goto(ref name) => gotos.push((name.clone(), path.clone(), stmt.1.clone())),
+            Stmt_::Block(ref block) => collect_goto_labels(&block.0, path, labels, gotos),
+            Stmt_::Declare(_, Some(ref block), _) |
+            Stmt_::Decl(Decl::Namespace(_, ref block, _)) => collect_goto_labels(&block.0, path, labels, gotos),
+            Stmt_::If(_, ref block, ref else_block, _) => {
+                collect_goto_labels(&block.0, path, labels, gotos);
+                collect_goto_labels(&else_block.0, path, labels, gotos);
+            }
+            Stmt_::Try(ref block, ref catch_clauses, ref finally_block) => {
+                collect_goto_labels(&block.0, path, labels, gotos);
+                for clause in catch_clauses {
+                    collect_goto_labels(&clause.block.0, path, labels, gotos);
+                }
+                if let Some(ref finally_block) = *finally_block {
+                    collect_goto_labels(&finally_block.0, path, labels, gotos);
+                }
+            }
+            Stmt_::While(_, ref block, _) |
+            Stmt_::DoWhile(ref block, _) |
+            Stmt_::For(_, _, _, ref block, _) |
+            Stmt_::ForEach(_, _, _, ref block, _) => {
+                path.push(stmt.1.start);
+                collect_goto_labels(&block.0, path, labels, gotos);
+                path.pop();
+            }
+            Stmt_::Switch(_, ref cases, _) => {
+                path.push(stmt.1.start);
+                for case in cases {
+                    collect_goto_labels(&case.block.0, path, labels, gotos);
+                }
+                path.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
 enum Associativity {
     Left,
     Right,
@@ -125,6 +361,8 @@ enum Precedence {
     LogicalAnd2,
     /// e.g. ternary
     Conditional,
+    /// null coalescing "??"
+    Coalesce,
     LogicalIncOr1,
     LogicalAnd1,
     BitwiseIncOr,
@@ -156,6 +394,7 @@ macro_rules! from_usize {
 }
 from_usize!(None,
             Conditional,
+            Coalesce,
             LogicalIncOr2,
             LogicalExcOr2,
             LogicalAnd2,
@@ -193,6 +432,7 @@ impl Token {
             Token::Mul | Token::Div | Token::Mod => Precedence::Mul,
             Token::Pow => Precedence::Pow,
             Token::QuestionMark => Precedence::Conditional,
+            Token::Coalesce => Precedence::Coalesce,
             Token::InstanceOf => Precedence::InstanceOf,
             _ => return None,
         })
@@ -206,7 +446,7 @@ impl Token {
             Token::SpaceShip | Token::Lt | Token::Gt | Token::IsSmallerOrEqual |
             Token::IsGreaterOrEqual | Token::Sl | Token::Sr | Token::Plus | Token::Minus |
             Token::Dot | Token::Mul | Token::Div | Token::Mod => Associativity::Left,
-            Token::Pow => Associativity::Right,
+            Token::Pow | Token::Coalesce => Associativity::Right,
             _ => unimplemented!(),
         }
     }
@@ -297,6 +537,9 @@ impl Parser {
                     _ => unreachable!(),
                 };
                 let expr = try!(self.parse_expression(Precedence::Unary));
+                if op == UnaryOp::PreInc || op == UnaryOp::PreDec {
+                    try!(check_write_context(&expr, self.pos));
+                }
                 let span = mk_span(left.1.start, expr.1.end);
                 Expr(Expr_::UnaryOp(op, Box::new(expr)), span)
             }
@@ -349,6 +592,7 @@ impl Parser {
                     Token::Div => Some(Op::Div),
                     Token::Mod => Some(Op::Mod),
                     Token::Pow => Some(Op::Pow),
+                    Token::Coalesce => Some(Op::Coalesce),
                     _ => None,
                 }),
                 None => (None, None),
@@ -411,9 +655,18 @@ impl Parser {
     }
 
     fn parse_simple_variable(&mut self) -> Result<(Variable, Span), ParserError> {
-        // TODO '$' '{' expr '}'
-        // TODO '$' simple_variable
-        if_lookahead!(self, Token::Dollar, _token, unimplemented!());
+        if_lookahead!(self, Token::Dollar, token, {
+            // '$' '{' expr '}'
+            if_lookahead!(self, Token::CurlyBracesOpen, _tok, {
+                let expr = try!(self.parse_expression(Precedence::None));
+                let end_pos = if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, tok, tok.1.end);
+                return Ok((Variable::Fetch(Box::new(expr)), mk_span(token.1.start, end_pos)));
+            });
+            // '$' simple_variable
+            let (var, span) = try!(self.parse_simple_variable());
+            let end_pos = span.end;
+            return Ok((Variable::Fetch(Box::new(Expr(Expr_::Variable(var), span))), mk_span(token.1.start, end_pos)));
+        });
         // T_VARIABLE
         if_lookahead!(self, Token::Variable(_), _token, Ok(match _token {
             TokenSpan(Token::Variable(varname), span) => (Variable::Name(varname.into()), span),
@@ -438,22 +691,47 @@ impl Parser {
         Ok(args)
     }
 
-    fn parse_argument_list(&mut self) -> Result<Vec<Expr>, ParserError> {
+    fn parse_argument_list(&mut self) -> Result<Vec<Argument>, ParserError> {
         if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen, _token, {
             if_lookahead!(self, Token::ParenthesesClose, _token, {
                 return Ok(vec![]);
             });
             // parse arguments (non_empty_argument_list)
             let mut args = vec![];
+            let mut has_named = false;
             loop {
-                let start_pos = if_lookahead!(self, Token::Ellipsis, token, Some(token.1.start), None);
-                let arg = try!(self.parse_expression(Precedence::None));
-                if let Some(start_pos) = start_pos {
-                    let span = Span { start: start_pos, ..arg.1.clone() };
-                    args.push(Expr(Expr_::Unpack(Box::new(arg)), span));
-                } else {
-                    args.push(arg);
+                let start = match self.next_token() {
+                    Some(token) => token.1.start,
+                    None => return Err(ParserError::new(vec![], self.pos)),
+                };
+                // named argument: identifier ':' expr
+                let name = match (self.next_token(), self.tokens.get(self.pos + 1)) {
+                    (Some(&TokenSpan(ref token, _)), Some(&TokenSpan(Token::Colon, _))) if token.is_reserved_non_modifier() || match *token {
+                        Token::String(_) => true,
+                        _ => false,
+                    } => {
+                        let name = try!(self.parse_identifier()).0;
+                        self.advance(1);
+                        Some(name)
+                    },
+                    _ => None,
+                };
+                let unpack = name.is_none() && if_lookahead!(self, Token::Ellipsis, _tok, true, false);
+                let by_ref = name.is_none() && !unpack && self.parse_is_ref();
+                let value = try!(self.parse_expression(Precedence::None));
+                let span = mk_span(start, value.1.end);
+                if name.is_some() {
+                    has_named = true;
+                } else if has_named {
+                    let message = if unpack {
+                        "Cannot use argument unpacking after named arguments"
+                    } else {
+                        "Cannot use positional argument after named argument"
+                    };
+                    // reported after the argument so it isn't overshadowed by other alternatives
+                    return Err(ParserError::syntax(SyntaxError::Invalid(message, span), self.pos));
                 }
+                args.push(Argument { name: name, value: value, by_ref: by_ref, unpack: unpack, span: span });
                 if_lookahead!(self, Token::Comma, _tok, {}, break);
             }
 
@@ -461,6 +739,31 @@ impl Parser {
         });
     }
 
+    /// parses all attribute groups `#[Name(args), ...]` in front of a declaration
+    fn parse_attributes(&mut self) -> Result<Vec<AttributeGroup>, ParserError> {
+        let mut groups = vec![];
+        while let Some(&TokenSpan(Token::AttributeStart, _)) = self.next_token() {
+            self.advance(1);
+            let mut attributes = vec![];
+            loop {
+                let name = try!(self.parse_name()).0;
+                let args = match self.next_token() {
+                    Some(&TokenSpan(Token::ParenthesesOpen, _)) => try!(self.parse_argument_list()),
+                    _ => vec![],
+                };
+                attributes.push(Attribute { name: name, args: args });
+                if_lookahead!(self, Token::Comma, _tok, {}, break);
+                // allow a trailing comma
+                if let Some(&TokenSpan(Token::SquareBracketClose, _)) = self.next_token() {
+                    break;
+                }
+            }
+            if_lookahead_expect!(self, Token::SquareBracketClose, Token::SquareBracketClose);
+            groups.push(AttributeGroup(attributes));
+        }
+        Ok(groups)
+    }
+
     fn parse_property_name(&mut self) -> Result<Expr, ParserError> {
         let old_pos = self.pos;
         alt!(self.parse_simple_variable_expr());
@@ -551,7 +854,7 @@ impl Parser {
         }
 
         let old_pos = self.pos;
-        let (mut var_expr, requires_appendix) = if let Some((var_expr, requires_appendix)) = base_item {
+        let (var_expr, requires_appendix) = if let Some((var_expr, requires_appendix)) = base_item {
             (var_expr, requires_appendix)
         } else {
             match parse_const_scoped(self, simple_only) {
@@ -569,8 +872,19 @@ impl Parser {
             }
         };
 
+        let (var_expr, has_appendix) = try!(self.parse_variable_appendixes(var_expr, simple_only));
+        // filter out the expression types that can't be alone
+        if requires_appendix && !has_appendix {
+            self.pos = old_pos;
+            return Err(ParserError::new(vec![], self.pos));
+        }
+
+        Ok(var_expr)
+    }
+
+    /// parse the "appendixes" of a variable: array indexing, function calls or members. also returns whether there were any
+    fn parse_variable_appendixes(&mut self, mut var_expr: Expr, simple_only: bool) -> Result<(Expr, bool), ParserError> {
         let mut i = 0;
-        // handle appendixes
         loop {
             i += 1;
             // array indexing
@@ -602,6 +916,22 @@ impl Parser {
                     continue;
                 }
             });
+            // nullsafe property fetch or method call
+            if_lookahead!(self, Token::NullsafeObjectOp, _tok, {
+                let prop = try!(self.parse_property_name());
+                var_expr = match self.next_token() {
+                    Some(&TokenSpan(Token::ParenthesesOpen, _)) if !simple_only => {
+                        let args = try!(self.parse_argument_list());
+                        let span = mk_span(var_expr.1.start, self.tokens[self.pos - 1].1.end);
+                        Expr(Expr_::NullsafeMethodCall(Box::new(var_expr), Box::new(prop), args), span)
+                    }
+                    _ => {
+                        let span = mk_span(var_expr.1.start, self.tokens[self.pos - 1].1.end);
+                        Expr(Expr_::NullsafeObjMember(Box::new(var_expr), Box::new(prop)), span)
+                    }
+                };
+                continue;
+            });
             // static member indexing
             if_lookahead!(self, Token::ScopeOp, _tok, match (self.parse_simple_variable_expr(), var_expr) {
                 (Err(_), var_expr_new) => var_expr = var_expr_new,
@@ -629,18 +959,12 @@ impl Parser {
             break;
         }
 
-        // filter out the expression types that can't be alone
-        if requires_appendix && i < 2 {
-            self.pos = old_pos;
-            return Err(ParserError::new(vec![], self.pos));
-        }
-
-        Ok(var_expr)
+        Ok((var_expr, i > 1))
     }
 
     fn parse_expression(&mut self, prec: Precedence) -> Result<Expr, ParserError> {
         let expr = try!(self.parse_unary_expression(prec));
-        Ok(expr)
+        array_from_short_array(expr, self.pos)
     }
 
     fn parse_opt_expression(&mut self, prec: Precedence) -> Result<Option<Expr>, ParserError> {
@@ -656,9 +980,11 @@ impl Parser {
         let expr = try!(self.parse_other_expression());
         let start_pos = expr.1.start;
         if_lookahead!(self, Token::Increment, token, {
+            try!(check_write_context(&expr, self.pos));
             return Ok(Expr(Expr_::UnaryOp(UnaryOp::PostInc, Box::new(expr)), mk_span(start_pos, token.1.end)));
         });
         if_lookahead!(self, Token::Decrement, token, {
+            try!(check_write_context(&expr, self.pos));
             return Ok(Expr(Expr_::UnaryOp(UnaryOp::PostDec, Box::new(expr)),  mk_span(start_pos, token.1.end)));
         });
         Ok(expr)
@@ -677,17 +1003,24 @@ impl Parser {
     fn parse_parameter_list(&mut self) -> (Vec<ParamDefinition>, Option<ParserError>) {
         let mut params = vec![];
         loop {
-            // type hint:
-            let nullable = if_lookahead!(self, Token::QuestionMark, _tok, true, false);
-            let mut ty = None;
-            if_lookahead!(self, Token::Array, _tok, ty=Some(Ty::Array), if_lookahead!(self, Token::Callable, _tok, ty=Some(Ty::Callable), {
-                match self.parse_name() {
-                    Ok((path, _)) => ty=Some(Ty::Object(Some(path))),
-                    Err(e) => if nullable {
-                        return (params, Some(e))
-                    },
-                }
-            }));
+            // the end of the list (which may be empty or have a trailing comma)
+            if let Some(&TokenSpan(Token::ParenthesesClose, _)) = self.next_token() {
+                break;
+            }
+            let attributes = match self.parse_attributes() {
+                Ok(attributes) => attributes,
+                Err(e) => return (params, Some(e)),
+            };
+            // type hint (the same grammar as for return and property types):
+            let ty = match self.next_token() {
+                Some(&TokenSpan(Token::Variable(_), _)) |
+                Some(&TokenSpan(Token::Ampersand, _)) |
+                Some(&TokenSpan(Token::Ellipsis, _)) => None,
+                _ => match self.parse_nullable_type() {
+                    Ok(ty) => Some(ty),
+                    Err(e) => return (params, Some(e)),
+                },
+            };
             let is_ref = self.parse_is_ref();
             let is_variadic = self.parse_is_variadic();
             // parameter name
@@ -705,6 +1038,7 @@ impl Parser {
                 Err(err) => return (params, Some(err)),
             }), None);
             params.push(ParamDefinition {
+                attributes: attributes,
                 name: param_name,
                 as_ref: is_ref,
                 variadic: is_variadic,
@@ -716,6 +1050,48 @@ impl Parser {
         (params, None)
     }
 
+    /// parses a type declaration of a parameter or a return type
+    fn parse_type(&mut self) -> Result<Ty, ParserError> {
+        if_lookahead!(self, Token::Array, _tok, return Ok(Ty::Array));
+        if_lookahead!(self, Token::Callable, _tok, return Ok(Ty::Callable));
+        let (path, _) = try!(self.parse_name());
+        // resolve builtin types, which are only valid as unqualified names
+        if path.kind == PathKind::Unqualified {
+            let ty = match &(path.identifier.borrow() as &str).to_lowercase()[..] {
+                "bool" => Some(Ty::Bool),
+                "float" => Some(Ty::Float),
+                "int" => Some(Ty::Int),
+                "string" => Some(Ty::String),
+                "void" => Some(Ty::Void),
+                "iterable" => Some(Ty::Iterable),
+                "object" => Some(Ty::Object(None)),
+                _ => None,
+            };
+            if let Some(ty) = ty {
+                return Ok(ty);
+            }
+        }
+        Ok(Ty::Object(Some(path)))
+    }
+
+    fn parse_nullable_type(&mut self) -> Result<NullableTy, ParserError> {
+        let nullable = if_lookahead!(self, Token::QuestionMark, _tok, true, false);
+        let mut ty = try!(self.parse_type());
+        // a nullable type cannot be part of a union
+        if !nullable {
+            let mut types = vec![];
+            while let Some(&TokenSpan(Token::BwOr, _)) = self.next_token() {
+                self.advance(1);
+                types.push(try!(self.parse_type()));
+            }
+            if !types.is_empty() {
+                types.insert(0, ty);
+                ty = Ty::Union(types);
+            }
+        }
+        Ok(NullableTy(ty, nullable))
+    }
+
     fn parse_function_declaration(&mut self,
                                   span: Span,
                                   parse_closure: bool,
@@ -725,6 +1101,9 @@ impl Parser {
         let returns_ref = self.parse_is_ref();
         let name = if parse_closure {
             None
+        } else if allow_abstract {
+            // methods may also be named like reserved words (e.g. list or fn)
+            Some(try!(self.parse_identifier()).0)
         } else {
             Some(if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, {
                 match token.0 {
@@ -735,7 +1114,10 @@ impl Parser {
         };
         if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
         let (params, params_err) = self.parse_parameter_list();
-        if_lookahead!(self, Token::ParenthesesClose, _tok, {}, return Err(params_err.unwrap()));
+        if let Some(err) = params_err {
+            return Err(err);
+        }
+        if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
         // lexical_vars (use clause)
         let mut use_variables = vec![];
         if parse_closure {
@@ -753,7 +1135,7 @@ impl Parser {
                 if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
             });
         }
-        // TODO: return_type
+        let ret_ty = if_lookahead!(self, Token::Colon, _tok, Some(try!(self.parse_nullable_type())), None);
         let no_body = if allow_abstract {
             if_lookahead!(self, Token::SemiColon, _tok, true, false)
         } else {
@@ -771,11 +1153,17 @@ impl Parser {
         } else {
             (None, None)
         };
+        if let Some(ref body) = body {
+            try!(self.check_goto_targets(&body.0));
+        }
         let decl = FunctionDecl {
             params: params,
             body: body,
             usev: use_variables,
             ret_ref: returns_ref,
+            is_static: false,
+            attributes: vec![],
+            ret_ty: ret_ty,
         };
         let span = mk_span(span.start, self.tokens[self.pos - 1].1.end);
         Ok(Stmt(match name {
@@ -784,14 +1172,48 @@ impl Parser {
         }, span))
     }
 
+    /// parses an arrow function `fn [&](params) [: type] => expr` after the `fn` token
+    fn parse_arrow_function(&mut self, span: Span, is_static: bool) -> Result<Expr, ParserError> {
+        let ret_ref = self.parse_is_ref();
+        if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
+        let (params, params_err) = self.parse_parameter_list();
+        if let Some(err) = params_err {
+            return Err(err);
+        }
+        if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
+        let ret_ty = if_lookahead!(self, Token::Colon, _tok, Some(try!(self.parse_nullable_type())), None);
+        if_lookahead_expect!(self, Token::DoubleArrow, Token::DoubleArrow);
+        let body = try!(self.parse_expression(Precedence::None));
+        // all variables used by the body are captured, except for the parameters and $this
+        let mut captures = vec![];
+        collect_variables(&body, &mut captures);
+        captures.retain(|var| var.borrow() as &str != "this" && params.iter().all(|param| param.name != *var));
+        let span = mk_span(span.start, body.1.end);
+        Ok(Expr(Expr_::ArrowFunction(ArrowFunctionDecl {
+            params: params,
+            body: Box::new(body),
+            ret_ref: ret_ref,
+            is_static: is_static,
+            attributes: vec![],
+            ret_ty: ret_ty,
+            captures: captures,
+        }), span))
+    }
+
     /// parses a class or trait declaration
     fn parse_oo_declaration(&mut self) -> Result<Stmt, ParserError> {
         enum OoType {
             Class,
             Trait,
             Interface,
+            Enum,
         }
-        let oo_type = if_lookahead!(self, Token::Trait, _tok, OoType::Trait, if_lookahead!(self, Token::Interface, _tok, OoType::Interface, OoType::Class));
+        let oo_type = if self.is_enum_declaration() {
+            self.advance(1);
+            OoType::Enum
+        } else {
+            if_lookahead!(self, Token::Trait, _tok, OoType::Trait, if_lookahead!(self, Token::Interface, _tok, OoType::Interface, OoType::Class))
+        };
 
         let mut class_modifiers = vec![];
         // only a class has modifiers (and a class token ofcourse)
@@ -808,6 +1230,16 @@ impl Parser {
             Token::String(str_) => str_,
             _ => unreachable!(),
         });
+        // enum Name: int|string
+        let backing_ty = match oo_type {
+            OoType::Enum => if_lookahead!(self, Token::Colon, _tok, {
+                match try!(self.parse_type()) {
+                    ty @ Ty::Int | ty @ Ty::String => Some(ty),
+                    _ => return Err(ParserError::message("Enum backing type must be int or string", self.pos)),
+                }
+            }, None),
+            _ => None,
+        };
         // extends are only valid for interfaces and classes
         let extends = match oo_type {
             OoType::Class => if_lookahead!(self, Token::Extends, _tok, Some(try!(self.parse_name()).0), None),
@@ -815,7 +1247,7 @@ impl Parser {
         };
         // implements = extended interfaces (equals to implements clause for classes and extends for interfaces)
         let implements_token = match oo_type {
-            OoType::Class => Some(Token::Implements),
+            OoType::Class | OoType::Enum => Some(Token::Implements),
             OoType::Interface => Some(Token::Extends),
             _ => None,
         };
@@ -833,41 +1265,268 @@ impl Parser {
         let end_pos = if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, token, token.1.end, if let Some(err) = err {
             return Err(err);
         });
+        let is_enum = match oo_type {
+            OoType::Enum => true,
+            _ => false,
+        };
+        if !is_enum && members.iter().any(|member| match *member { Member::EnumCase(..) => true, _ => false }) {
+            return Err(ParserError::message("Case can only be used in enums", self.pos));
+        }
         let span = mk_span(start_pos, end_pos);
         let ret_expr = match oo_type {
             OoType::Class => Stmt_::Decl(Decl::Class(ClassDecl {
+                attributes: vec![],
                 cmod: ClassModifiers::new(&class_modifiers),
                 name: name,
                 base_class: extends,
                 implements: implements,
                 members: members,
             })),
-            OoType::Interface => Stmt_::Decl(Decl::Interface(name, implements, members)),
-            OoType::Trait => Stmt_::Decl(Decl::Trait(name, members)),
+            OoType::Interface => Stmt_::Decl(Decl::Interface(vec![], name, implements, members)),
+            OoType::Trait => Stmt_::Decl(Decl::Trait(vec![], name, members)),
+            OoType::Enum => Stmt_::Decl(Decl::Enum(EnumDecl {
+                attributes: vec![],
+                name: name,
+                backing_ty: backing_ty,
+                implements: implements,
+                members: members,
+            })),
         };
         Ok(Stmt(ret_expr, span))
     }
 
-    /// parsing all expressions after the precedence applying (stage 2 "callback")
-    fn parse_other_expression(&mut self) -> Result<Expr, ParserError> {
-        let mut deepest_err: Option<(usize, ParserError)> = None;
+    /// `new` class_name_reference ctor_arguments or an anonymous class, after the `new` token
+    fn parse_new_expression(&mut self, token: TokenSpan) -> Result<Expr, ParserError> {
+        // anonymous class, which may be attributed: new #[A] class {}
+        let attributes = try!(self.parse_attributes());
+        if_lookahead!(self, Token::Class, _tok, {
+            let args = if let Some(&TokenSpan(Token::ParenthesesOpen, _)) = self.next_token() {
+                try!(self.parse_argument_list())
+            } else {
+                vec![]
+            };
+            let extends = if_lookahead!(self, Token::Extends, _tok, Some(try!(self.parse_name()).0), None);
+            let implements = if_lookahead!(self, Token::Implements, _tok, try!(self.parse_name_list()).into_iter().map(|x| x.0).collect(), vec![]);
+            if_lookahead_expect!(self, Token::CurlyBracesOpen, Token::CurlyBracesOpen);
+            let (members, err) = self.parse_class_statement_list();
+            let end_pos = if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, token, token.1.end, if let Some(err) = err {
+                return Err(err);
+            });
+            return Ok(Expr(Expr_::AnonymousClass(attributes, args, extends, implements, members), Span { end: end_pos, ..token.1 }));
+        });
+        if !attributes.is_empty() {
+            return Err(ParserError::new(vec![Token::Class], self.pos));
+        }
+        let x = try!(self.parse_class_name_reference());
+        let args = if let Some(&TokenSpan(Token::ParenthesesOpen, _)) = self.next_token() {
+            try!(self.parse_argument_list())
+        } else {
+            vec![]
+        };
+        let span = Span { end: self.tokens[self.pos-1].1.end, ..token.1 };
+        Ok(Expr(Expr_::New(Box::new(x), args), span))
+    }
 
-        // new
-        if_lookahead!(self, Token::New, token, {
-            match self.parse_class_name_reference() {
-                Ok(x) => {
-                    let args = if let Some(&TokenSpan(Token::ParenthesesOpen, _)) = self.next_token() {
-                        try!(self.parse_argument_list())
-                    } else {
-                        vec![]
+    /// `match` '(' expr ')' '{' match_arm_list '}', after the `match` token
+    fn parse_match_expression(&mut self, token: TokenSpan) -> Result<Expr, ParserError> {
+        if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
+        let subject = try!(self.parse_expression(Precedence::None));
+        if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
+        if_lookahead_expect!(self, Token::CurlyBracesOpen, Token::CurlyBracesOpen);
+        let mut arms: Vec<MatchArm> = vec![];
+        loop {
+            if let Some(&TokenSpan(Token::CurlyBracesClose, _)) = self.next_token() {
+                break;
+            }
+            let default_pos = self.pos;
+            let (conds, is_default) = if_lookahead!(self, Token::Default, _tok, {
+                if arms.iter().any(|arm| arm.default) {
+                    return Err(ParserError::message("Match expressions may only contain one default arm", default_pos));
+                }
+                if_lookahead!(self, Token::Comma, _tok, {});
+                (vec![], true)
+            }, {
+                // a list of conditions, optionally followed by a trailing comma
+                let mut conds = vec![];
+                loop {
+                    conds.push(try!(self.parse_expression(Precedence::None)));
+                    if_lookahead!(self, Token::Comma, _tok, {}, break);
+                    if let Some(&TokenSpan(Token::DoubleArrow, _)) = self.next_token() {
+                        break;
+                    }
+                }
+                (conds, false)
+            });
+            if_lookahead_expect!(self, Token::DoubleArrow, Token::DoubleArrow);
+            let body = try!(self.parse_expression(Precedence::None));
+            arms.push(MatchArm { conds: conds, default: is_default, body: body });
+            if_lookahead!(self, Token::Comma, _tok, {}, break);
+        }
+        if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose);
+        let span = mk_span(token.1.start, self.tokens[self.pos - 1].1.end);
+        Ok(Expr(Expr_::Match(Box::new(subject), arms), span))
+    }
+
+    /// internal_functions_in_yacc (isset, empty, eval, include, require) and casts, after their token
+    fn parse_internal_function(&mut self, token: Token, mut span: Span) -> Result<Expr, ParserError> {
+        // several cast operators
+        let cast_ty = match token {
+            Token::CastInt => Some(CastTy::Int),
+            Token::CastInteger => Some(CastTy::Integer),
+            Token::CastDouble => Some(CastTy::Double),
+            Token::CastFloat => Some(CastTy::Float),
+            Token::CastReal => Some(CastTy::Real),
+            Token::CastString => Some(CastTy::String),
+            Token::CastBinary => Some(CastTy::Binary),
+            Token::CastArray => Some(CastTy::Array),
+            Token::CastObject => Some(CastTy::Object),
+            Token::CastBool => Some(CastTy::Bool),
+            Token::CastBoolean => Some(CastTy::Boolean),
+            Token::CastUnset => Some(CastTy::Unset),
+            _ => None,
+        };
+        if let Some(cast_ty) = cast_ty {
+            let expr = try!(self.parse_expression(Precedence::Unary));
+            span.end = expr.1.end;
+            return Ok(Expr(Expr_::Cast(cast_ty, Box::new(expr)), span));
+        }
+        // isset/empty/eval
+        match token {
+            Token::Isset | Token::Empty | Token::Eval => {
+                if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen, _tok, {
+                    let mut args = vec![];
+                    while {
+                        args.push(try!(self.parse_expression(Precedence::None)));
+                        if let Token::Isset = token {
+                            if_lookahead!(self, Token::Comma, _token, true, false)
+                        } else {
+                            false
+                        }
+                    } {}
+                    span.end = if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose, token, token.1.end);
+                    let expr = match token {
+                        Token::Isset => Expr_::Isset(args),
+                        Token::Empty => {
+                            assert_eq!(args.len(), 1);
+                            Expr_::Empty(Box::new(args.pop().unwrap()))
+                        },
+                        Token::Eval => Expr_::Eval(Box::new(args.pop().unwrap())),
+                        _ => unreachable!(),
                     };
-                    let span = Span { end: self.tokens[self.pos-1].1.end, ..token.1 };
-                    return Ok(Expr(Expr_::New(Box::new(x), args), span));
-                },
-                Err(x) => return Err(x),
+                    return Ok(Expr(expr, span))
+                });
             }
-            // TODO: anonymous class
+            _ => (),
+        }
+        // include/require
+        let ity = match token {
+            Token::Include => IncludeTy::Include,
+            Token::IncludeOnce => IncludeTy::IncludeOnce,
+            Token::Require => IncludeTy::Require,
+            Token::RequireOnce => IncludeTy::RequireOnce,
+            _ => unreachable!(),
+        };
+        let expr = try!(self.parse_expression(Precedence::None));
+        Ok(Expr(Expr_::Include(ity, Box::new(expr)), mk_span(span.start, self.tokens[self.pos - 1].1.end)))
+    }
+
+    /// a (compound) assignment to the given variable or destructuring, or just the variable itself
+    fn parse_assignment(&mut self, var: Expr) -> Result<Expr, ParserError> {
+        // variable '=' expr
+        // variable '=' '&' variable
+        // and all variable T_<OP>_ASSIGNs
+        let assign_type = match self.next_token() {
+            Some(&TokenSpan(ref x, _)) => match *x {
+                Token::Equal => Some(Op::Eq),
+                Token::PlusEqual => Some(Op::Add),
+                Token::MinusEqual => Some(Op::Sub),
+                Token::MulEqual => Some(Op::Mul),
+                Token::PowEqual => Some(Op::Pow),
+                Token::DivEqual => Some(Op::Div),
+                Token::ConcatEqual => Some(Op::Concat),
+                Token::ModEqual => Some(Op::Mod),
+                Token::AndEqual => Some(Op::And),
+                Token::XorEqual => Some(Op::BitwiseExclOr),
+                Token::SlEqual => Some(Op::Sl),
+                Token::SrEqual => Some(Op::Sr),
+                Token::CoalesceEqual => Some(Op::Coalesce),
+                _ => None,
+            },
+            None => None,
+        };
+        if let Some(assign_type) = assign_type {
+            self.advance(1);
+            let by_ref = match (&assign_type, self.next_token()) {
+                (&Op::Eq, Some(&TokenSpan(Token::Ampersand, _))) => {
+                    self.advance(1);
+                    true
+                }
+                _ => false,
+            };
+
+            return match self.parse_expression(Precedence::None) {
+                Ok(expr) => {
+                    try!(check_write_context(&var, self.pos));
+                    if by_ref {
+                        try!(check_reference_context(&expr, self.pos));
+                    }
+                    let span = mk_span(var.1.start, self.tokens[self.pos - 1].1.end);
+                    let expr = match (assign_type, by_ref) {
+                        (Op::Eq, false) => Expr_::Assign(Box::new(var), Box::new(expr)),
+                        (Op::Eq, true) => Expr_::AssignRef(Box::new(var), Box::new(expr)),
+                        (op, _) => Expr_::CompoundAssign(Box::new(var), op, Box::new(expr)),
+                    };
+                    Ok(Expr(expr, span))
+                }
+                x => x,
+            };
+        }
+        Ok(var)
+    }
+
+    /// an anonymous function or arrow function, optionally attributed and/or static
+    fn parse_closure(&mut self) -> Result<Expr, ParserError> {
+        // attributed closure or arrow function
+        if let Some(&TokenSpan(Token::AttributeStart, ref span)) = self.next_token() {
+            let start = span.start;
+            let attributes = try!(self.parse_attributes());
+            let pos = self.pos;
+            return match try!(self.parse_closure()) {
+                Expr(Expr_::Function(mut decl), span) => {
+                    decl.attributes = attributes;
+                    Ok(Expr(Expr_::Function(decl), mk_span(start, span.end)))
+                }
+                Expr(Expr_::ArrowFunction(mut decl), span) => {
+                    decl.attributes = attributes;
+                    Ok(Expr(Expr_::ArrowFunction(decl), mk_span(start, span.end)))
+                }
+                _ => Err(ParserError::new(vec![Token::Function, Token::Fn], pos)),
+            };
+        }
+        // function declaration (anonymous function)
+        if_lookahead!(self, Token::Function, token, if let Stmt_::Expr(e) = try!(self.parse_function_declaration(token.1, true, false)).0 {
+            return Ok(e)
         });
+        if_lookahead!(self, Token::Fn, token, return self.parse_arrow_function(token.1, false));
+        // static closure
+        if let (Some(&TokenSpan(Token::Static, ref span)), Some(&TokenSpan(Token::Function, _))) = (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            let span = span.clone();
+            self.advance(2);
+            if let Stmt_::Expr(Expr(Expr_::Function(mut decl), span)) = try!(self.parse_function_declaration(span, true, false)).0 {
+                decl.is_static = true;
+                return Ok(Expr(Expr_::Function(decl), span));
+            }
+        }
+        if let (Some(&TokenSpan(Token::Static, ref span)), Some(&TokenSpan(Token::Fn, _))) = (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            let span = span.clone();
+            self.advance(2);
+            return self.parse_arrow_function(span, true);
+        }
+        Err(ParserError::new(vec![Token::Function, Token::Fn], self.pos))
+    }
+
+    /// clone, exit, print or yield followed by an expression
+    fn parse_keyword_expression(&mut self) -> Result<Expr, ParserError> {
         if_lookahead!(self, Token::Clone, token, {
             return Ok(Expr(Expr_::Clone(Box::new(try!(self.parse_expression(Precedence::None)))), token.1));
         });
@@ -879,81 +1538,56 @@ impl Parser {
             }, None);
             return Ok(Expr(Expr_::Exit(expr.map(Box::new)), span));
         });
+        if_lookahead!(self, Token::Print, token, {
+            let expr = try!(self.parse_expression(Precedence::LogicalAnd2));
+            let span = mk_span(token.1.start, expr.1.end);
+            return Ok(Expr(Expr_::Print(Box::new(expr)), span));
+        });
         if_lookahead!(self, Token::Yield, token, {
             let expr = try!(self.parse_opt_expression(Precedence::None)).map(Box::new);
             return Ok(Expr(Expr_::Yield(expr), mk_span(token.1.start, self.tokens[self.pos-1].1.end)));
         });
-        // function declaration (anonymous function)
-        if_lookahead!(self, Token::Function, token, if let Stmt_::Expr(e) = try!(self.parse_function_declaration(token.1, true, false)).0 {
-            return Ok(e)
-        });
+        Err(ParserError::new(vec![Token::Clone, Token::Exit, Token::Print, Token::Yield], self.pos))
+    }
+
+    /// parsing all expressions after the precedence applying (stage 2 "callback")
+    fn parse_other_expression(&mut self) -> Result<Expr, ParserError> {
+        let mut deepest_err: Option<(usize, ParserError)> = None;
+
+        // new
+        if_lookahead!(self, Token::New, token, return self.parse_new_expression(token));
+        // match '(' expr ')' '{' match_arm_list '}'
+        if_lookahead!(self, Token::Match, token, return self.parse_match_expression(token));
+        match self.next_token() {
+            Some(&TokenSpan(Token::Clone, _)) | Some(&TokenSpan(Token::Exit, _)) |
+            Some(&TokenSpan(Token::Print, _)) | Some(&TokenSpan(Token::Yield, _)) => return self.parse_keyword_expression(),
+            _ => (),
+        }
+        // closures and arrow functions, which may be attributed or static
+        match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            (Some(&TokenSpan(Token::AttributeStart, _)), _) |
+            (Some(&TokenSpan(Token::Function, _)), _) |
+            (Some(&TokenSpan(Token::Fn, _)), _) |
+            (Some(&TokenSpan(Token::Static, _)), Some(&TokenSpan(Token::Function, _))) |
+            (Some(&TokenSpan(Token::Static, _)), Some(&TokenSpan(Token::Fn, _))) => return self.parse_closure(),
+            _ => (),
+        }
         // internal_functions_in_yacc / casts
         let ret = match self.next_token() {
             Some(&TokenSpan(ref x, ref span)) => match *x {
                     Token::Include| Token::IncludeOnce | Token::Require | Token::RequireOnce |
-                    Token::Isset | Token::Empty | Token::CastInt | Token::CastDouble | Token::CastString |
-                    Token::CastArray | Token::CastObject | Token::CastBool | Token::CastUnset => Some((x.clone(), span.clone())),
+                    Token::Isset | Token::Empty | Token::Eval | Token::CastInt | Token::CastInteger | Token::CastDouble |
+                    Token::CastFloat | Token::CastReal | Token::CastString | Token::CastBinary | Token::CastArray |
+                    Token::CastObject | Token::CastBool | Token::CastBoolean | Token::CastUnset => Some((x.clone(), span.clone())),
                     _ => None,
             },
             None => None,
         };
-        if let Some((token, mut span)) = ret {
+        if let Some((token, span)) = ret {
             self.advance(1);
-            // several cast operators
-            let cast_ty = match token {
-                Token::CastInt => Some(Ty::Int),
-                Token::CastDouble => Some(Ty::Double),
-                Token::CastString => Some(Ty::String),
-                Token::CastArray => Some(Ty::Array),
-                Token::CastObject => Some(Ty::Object(None)),
-                Token::CastBool => Some(Ty::Bool),
-                Token::CastUnset => unimplemented!(),
-                _ => None,
-            };
-            if let Some(cast_ty) = cast_ty {
-                let expr = try!(self.parse_expression(Precedence::Unary));
-                span.end = expr.1.end;
-                return Ok(Expr(Expr_::Cast(cast_ty, Box::new(expr)), span));
-            }
-            // isset/empty
-            match token {
-                Token::Isset | Token::Empty => {
-                    if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen, _tok, {
-                        let mut args = vec![];
-                        while {
-                            args.push(try!(self.parse_expression(Precedence::None)));
-                            if let Token::Isset = token {
-                                if_lookahead!(self, Token::Comma, _token, true, false)
-                            } else {
-                                false
-                            }
-                        } {}
-                        span.end = if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose, token, token.1.end);
-                        let expr = match token {
-                            Token::Isset => Expr_::Isset(args),
-                            Token::Empty => {
-                                assert_eq!(args.len(), 1);
-                                Expr_::Empty(Box::new(args.pop().unwrap()))
-                            },
-                            _ => unreachable!(),
-                        };
-                        return Ok(Expr(expr, span))
-                    });
-                }
-                _ => (),
-            }
-            // include/require
-            let ity = match token {
-                Token::Include => IncludeTy::Include,
-                Token::IncludeOnce => IncludeTy::IncludeOnce,
-                Token::Require => IncludeTy::Require,
-                Token::RequireOnce => IncludeTy::RequireOnce,
-                _ => unreachable!(),
-            };
-            let expr = try!(self.parse_expression(Precedence::None));
-            return Ok(Expr(Expr_::Include(ity, Box::new(expr)), mk_span(span.start, self.tokens[self.pos - 1].1.end)));
+            return self.parse_internal_function(token, span);
         }
-        // variable handling
+        // variable handling (including short lists, see parse_dereferencable_scalar)
         let assign_target = match self.parse_variable(false, None) {
             Ok(x) => Some(x),
             Err(x) => {
@@ -965,67 +1599,24 @@ impl Parser {
         // parse a list_statement (which is only valid as assign_target)
         let assign_target = match assign_target {
             Some(x) => Some(x),
-            None => if_lookahead!(self, Token::List, token, {
-                if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
-                let pairs = try!(self.parse_array_pair_list());
-                let end_pos = if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose, token, token.1.end);
-                // only valid as assign target
-                match self.next_token() {
-                    Some(&TokenSpan(Token::Equal, _)) => (),
-                    _ => return Err(ParserError::new(vec![Token::Equal], self.pos)),
+            None => match self.next_token() {
+                Some(&TokenSpan(Token::List, _)) => {
+                    let list = try!(self.parse_destructuring());
+                    // only valid as assign target
+                    match self.next_token() {
+                        Some(&TokenSpan(Token::Equal, _)) => (),
+                        _ => return Err(ParserError::new(vec![Token::Equal], self.pos)),
+                    }
+                    try!(check_destructuring(&list, self.pos));
+                    Some(list)
                 }
-                let span = mk_span(token.1.start, end_pos);
-                Some(Expr(Expr_::List(pairs), span))
-            }, None),
+                _ => None,
+            },
         };
 
         if let Some(var) = assign_target {
-            // variable '=' expr
-            // variable '=' '&' variable
-            // and all variable T_<OP>_ASSIGNs
-            let assign_type = match self.next_token() {
-                Some(&TokenSpan(ref x, _)) => match *x {
-                    Token::Equal => Some(Op::Eq),
-                    Token::PlusEqual => Some(Op::Add),
-                    Token::MinusEqual => Some(Op::Sub),
-                    Token::MulEqual => Some(Op::Mul),
-                    Token::PowEqual => Some(Op::Pow),
-                    Token::DivEqual => Some(Op::Div),
-                    Token::ConcatEqual => Some(Op::Concat),
-                    Token::ModEqual => Some(Op::Mod),
-                    Token::AndEqual => Some(Op::And),
-                    Token::XorEqual => Some(Op::BitwiseExclOr),
-                    Token::SlEqual => Some(Op::Sl),
-                    Token::SrEqual => Some(Op::Sr),
-                    _ => None,
-                },
-                None => None,
-            };
-            if let Some(assign_type) = assign_type {
-                self.advance(1);
-                let by_ref = match (&assign_type, self.next_token()) {
-                    (&Op::Eq, Some(&TokenSpan(Token::Ampersand, _))) => {
-                        self.advance(1);
-                        true
-                    }
-                    _ => false,
-                };
-
-                return match self.parse_expression(Precedence::None) {
-                    Ok(expr) => {
-                        let span = mk_span(var.1.start, self.tokens[self.pos - 1].1.end);
-                        let expr = match (assign_type, by_ref) {
-                            (Op::Eq, false) => Expr_::Assign(Box::new(var), Box::new(expr)),
-                            (Op::Eq, true) => Expr_::AssignRef(Box::new(var), Box::new(expr)),
-                            (op, _) => Expr_::CompoundAssign(Box::new(var), op, Box::new(expr)),
-                        };
-                        Ok(Expr(expr, span))
-                    }
-                    x => x,
-                };
-            }
-            return Ok(var);
-        };
+            return self.parse_assignment(var);
+        }
 
         // '(' expr ')'
         if_lookahead!(self, Token::ParenthesesOpen, token, {
@@ -1076,15 +1667,24 @@ impl Parser {
     }
 
     fn parse_name(&mut self) -> Result<(Path, Span), ParserError> {
-        // TODO: |   T_NAMESPACE T_NS_SEPARATOR namespace_name   { $$ = $3; $$->attr = ZEND_NAME_RELATIVE; }
         // try to consume the \\ if one exists so that a namespace_name will be matched
-        // then the path will be absolute
-        let is_absolute = if_lookahead!(self, Token::NsSeparator, _token, true, false);
+        // then the path will be absolute, or namespace\\ for a path relative to the current namespace
+        let start_pos = self.pos;
+        let kind = match (self.tokens.get(self.pos).map(|x| &x.0), self.tokens.get(self.pos + 1).map(|x| &x.0)) {
+            (Some(&Token::NsSeparator), _) => Some(PathKind::FullyQualified),
+            (Some(&Token::Namespace), Some(&Token::NsSeparator)) => Some(PathKind::Relative),
+            _ => None,
+        };
+        match kind {
+            Some(PathKind::FullyQualified) => self.advance(1),
+            Some(_) => self.advance(2),
+            None => (),
+        }
         match self.parse_namespace_name() {
             Ok((mut path, mut span)) => {
-                if is_absolute {
-                    span.start -= 1;
-                    path.is_absolute = true;
+                if let Some(kind) = kind {
+                    span.start = self.tokens[start_pos].1.start;
+                    path.kind = kind;
                 }
                 Ok((path, span))
             }
@@ -1173,33 +1773,47 @@ impl Parser {
         Ok(name)
     }
 
-    fn parse_encaps_list(&mut self) -> Result<Expr, ParserError> {
-        let mut str_ = String::new();
+    /// parse the literal and interpolated parts of a string
+    /// a string literal which is only a `String` if it's valid UTF-8 (escape sequences may produce any bytes)
+    fn mk_string_expr(&mut self, bytes: Vec<u8>, span: Span) -> Expr {
+        match String::from_utf8(bytes) {
+            Ok(str_) => Expr(Expr_::String(self.interner.intern(&str_)), span),
+            Err(err) => Expr(Expr_::BinaryString(Rc::new(err.into_bytes())), span),
+        }
+    }
+
+    fn parse_encaps_parts(&mut self) -> Result<Vec<Expr>, ParserError> {
+        let mut str_: Vec<u8> = vec![];
         let mut parts = vec![];
         let mut start_pos = None;
         let mut end_pos = 0;
         // find string literals
         loop {
-            if_lookahead!(self, Token::ConstantEncapsedString(_), token, {
-                match token.0 {
-                    Token::ConstantEncapsedString(str_part) => {
-                        if start_pos.is_none() {
-                            start_pos = Some(token.1.start);
-                        }
-                        if end_pos < token.1.end {
-                            end_pos = token.1.end;
-                        }
-                        str_.push_str(str_part.borrow());
-                        continue;
-                    },
-                    _ => unreachable!(),
+            match self.next_token().cloned() {
+                Some(TokenSpan(Token::ConstantEncapsedString(_), _)) |
+                Some(TokenSpan(Token::BinaryCharSequence(_), _)) => {
+                    let token = self.next_token().unwrap().clone();
+                    self.advance(1);
+                    if start_pos.is_none() {
+                        start_pos = Some(token.1.start);
+                    }
+                    if end_pos < token.1.end {
+                        end_pos = token.1.end;
+                    }
+                    match token.0 {
+                        Token::ConstantEncapsedString(str_part) => str_.extend((str_part.borrow() as &str).as_bytes()),
+                        Token::BinaryCharSequence(bytes) => str_.extend(bytes.iter()),
+                        _ => unreachable!(),
+                    }
+                    continue;
                 }
-            });
+                _ => (),
+            }
             if_lookahead!(self, Token::DollarCurlyBracesOpen, token, {
                 if !str_.is_empty() {
-                    parts.push(Expr(Expr_::String(self.interner.intern(&str_)), mk_span(start_pos.unwrap(), end_pos)));
+                    let expr = self.mk_string_expr(mem::replace(&mut str_, vec![]), mk_span(start_pos.unwrap(), end_pos));
+                    parts.push(expr);
                 }
-                str_.clear();
                 match self.parse_identifier() {
                     Ok((name, span)) => {
                         let mut  expr = try!(self.parse_variable(false, Some((Expr(Expr_::Variable(Variable::Name(name)), span), false))));
@@ -1217,9 +1831,9 @@ impl Parser {
             });
             if_lookahead!(self, Token::CurlyBracesOpen, _tok, {
                 if !str_.is_empty() {
-                    parts.push(Expr(Expr_::String(self.interner.intern(&str_)), mk_span(start_pos.unwrap(), end_pos)));
+                    let expr = self.mk_string_expr(mem::replace(&mut str_, vec![]), mk_span(start_pos.unwrap(), end_pos));
+                    parts.push(expr);
                 }
-                str_.clear();
                 let expr = try!(self.parse_expression(Precedence::None));
                 end_pos = if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, token, token.1.end);
                 start_pos = Some(end_pos);
@@ -1228,9 +1842,9 @@ impl Parser {
             });
             if let Ok(expr) = self.parse_variable(false, None) {
                 if !str_.is_empty() {
-                    parts.push(Expr(Expr_::String(self.interner.intern(&str_)), mk_span(start_pos.unwrap(), end_pos)));
+                    let expr = self.mk_string_expr(mem::replace(&mut str_, vec![]), mk_span(start_pos.unwrap(), end_pos));
+                    parts.push(expr);
                 }
-                str_.clear();
                 start_pos = Some(expr.1.end);
                 end_pos = expr.1.end;
                 parts.push(expr);
@@ -1238,30 +1852,22 @@ impl Parser {
             }
             break;
         }
-        let str_expr = if !str_.is_empty() {
-            let span = Span {
-              start: start_pos.unwrap(),
-              end: end_pos,
-              ..Span::new()
-            };
-            Some(Expr(Expr_::String(self.interner.intern(&str_)), span))
-        } else {
-            None
-        };
-        if !parts.is_empty() {
-            let initial_expr = match str_expr {
-                Some(x) => x,
-                None => parts.pop().unwrap(),
-            };
+        if !str_.is_empty() {
+            let expr = self.mk_string_expr(str_, mk_span(start_pos.unwrap(), end_pos));
+            parts.push(expr);
+        }
+        Ok(parts)
+    }
+
+    fn parse_encaps_list(&mut self) -> Result<Expr, ParserError> {
+        let mut parts = try!(self.parse_encaps_parts());
+        if let Some(initial_expr) = parts.pop() {
             // concat all parts
             return Ok(parts.into_iter().rev().fold(initial_expr, |acc, part| {
                 let span = mk_span(part.1.start, acc.1.end);
                 Expr(Expr_::BinaryOp(Op::Concat, Box::new(part), Box::new(acc)), span)
             }));
         }
-        if let Some(str_expr) = str_expr {
-            return Ok(str_expr);
-        }
         // use this to generate our error, does not anything related to the grammar
         if_lookahead_expect!(self, Token::ConstantEncapsedString(_), Token::ConstantEncapsedString(self.interner.intern("")));
         unreachable!();
@@ -1270,15 +1876,25 @@ impl Parser {
     fn parse_dereferencable_scalar(&mut self) -> Result<Expr, ParserError> {
         if_lookahead!(self, Token::Array, token, {
             if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen, _tok, {
-                let pairs = try!(self.parse_array_pair_list());
+                let pairs = try!(self.parse_array_pairs());
                 let end_pos = if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose, token, token.1.end);
                 return Ok(Expr(Expr_::Array(pairs), Span { start: token.1.start, end: end_pos, ..Span::new() }));
             });
         });
         if_lookahead!(self, Token::SquareBracketOpen, token, {
-            let pairs = try!(self.parse_array_pair_list());
-            let end_pos = if_lookahead_expect!(self, Token::SquareBracketClose, Token::SquareBracketClose, token, token.1.end);
-            return Ok(Expr(Expr_::Array(pairs), Span { start: token.1.start, end: end_pos, ..Span::new() }));
+            let list = try!(self.parse_short_array(token.1.start));
+            // a short list can only be distinguished from an array by the following '='
+            // if the expression ends here it's kept undecided, since an enclosing array may still turn out to be a
+            // destructuring. parse_expression (or the enclosing array) converts it then
+            return match self.next_token() {
+                Some(&TokenSpan(Token::Equal, _)) => {
+                    try!(check_destructuring(&list, self.pos));
+                    Ok(list)
+                }
+                Some(&TokenSpan(Token::Comma, _)) |
+                Some(&TokenSpan(Token::SquareBracketClose, _)) => Ok(list),
+                _ => array_from_short_array(list, self.pos),
+            };
         });
         if_lookahead!(self, Token::ConstantEncapsedString(_), token, {
             match token.0 {
@@ -1286,6 +1902,12 @@ impl Parser {
                 _ => unreachable!(),
             }
         });
+        if_lookahead!(self, Token::BinaryCharSequence(_), token, {
+            match token.0 {
+                Token::BinaryCharSequence(bytes) => return Ok(Expr(Expr_::BinaryString(bytes), token.1)),
+                _ => unreachable!(),
+            }
+        });
         let expected = vec![Token::Array, Token::SquareBracketOpen, Token::ConstantEncapsedString(self.interner.intern(""))];
         Err(ParserError::new(expected, self.pos))
     }
@@ -1324,6 +1946,12 @@ impl Parser {
                     ret.1.end = if_lookahead_expect!(self, Token::HereDocEnd, Token::HereDocEnd, token, token.1.end);
                     return Ok(ret);
                 },
+                // '`' backticks_expr '`'
+                Token::Backquote => {
+                    let parts = try!(self.parse_encaps_parts());
+                    let end_pos = if_lookahead_expect!(self, Token::Backquote, Token::Backquote, token, token.1.end);
+                    return Ok(Expr(Expr_::ShellExec(parts), mk_span(x.1.start, end_pos)));
+                },
                 _ => {
                     self.advance(-1);
                     // TODO: check which error of dereferencable_scalar, parse_constant goes deeper
@@ -1335,22 +1963,98 @@ impl Parser {
         }
     }
 
-    fn parse_array_pair_list(&mut self) -> Result<Vec<(Option<Expr>, Expr)>, ParserError> {
-        // parse array pairs as long as possible
+    /// parse the elements of an array or list, skipped elements (e.g. `list(, $b)`) are None
+    fn parse_array_pair_list(&mut self, is_list: bool) -> Result<Vec<Option<(Option<Expr>, Expr)>>, ParserError> {
         let mut pairs = vec![];
-        while let Ok(expr) = self.parse_expression(Precedence::None) {
-            let kv_pair = if_lookahead!(self, Token::DoubleArrow, _tok, {
-                (Some(expr), try!(self.parse_expression(Precedence::None)))
-            }, {(None, expr)});
-            pairs.push(kv_pair);
+        loop {
+            let pair = match self.next_token() {
+                // only a comma right away skips an element
+                Some(&TokenSpan(Token::Comma, _)) => None,
+                // the end of the elements, also after a trailing comma
+                Some(&TokenSpan(Token::SquareBracketClose, _)) |
+                Some(&TokenSpan(Token::ParenthesesClose, _)) => break,
+                _ => {
+                    let expr = try!(self.parse_array_pair_value(is_list));
+                    Some(if_lookahead!(self, Token::DoubleArrow, _tok, {
+                        (Some(expr), try!(self.parse_array_pair_value(is_list)))
+                    }, {(None, expr)}))
+                }
+            };
+            pairs.push(pair);
             if_lookahead!(self, Token::Comma, _token, {}, break);
         }
         Ok(pairs)
     }
 
+    /// parse the pairs of an array literal, which must not contain skipped elements
+    fn parse_array_pairs(&mut self) -> Result<Vec<(Option<Expr>, Expr)>, ParserError> {
+        let pairs = try!(self.parse_array_pair_list(false));
+        array_pairs_from_list(pairs, self.pos)
+    }
+
+    /// parse a short array `[...]` after the opening bracket, it's kept as `Expr_::List` (which allows skipped elements)
+    /// until it's known whether it's an array or a destructuring
+    fn parse_short_array(&mut self, start: u32) -> Result<Expr, ParserError> {
+        let pairs = try!(self.parse_array_pair_list(false));
+        let end_pos = if_lookahead_expect!(self, Token::SquareBracketClose, Token::SquareBracketClose, token, token.1.end);
+        Ok(Expr(Expr_::List(pairs, true), mk_span(start, end_pos)))
+    }
+
+    /// parse the value of an array pair: `&variable`, a nested list (only within lists) or any expression
+    fn parse_array_pair_value(&mut self, is_list: bool) -> Result<Expr, ParserError> {
+        if_lookahead!(self, Token::Ampersand, token, {
+            let expr = try!(self.parse_variable(false, None));
+            let expr = try!(array_from_short_array(expr, self.pos));
+            try!(check_reference_context(&expr, self.pos));
+            let span = mk_span(token.1.start, expr.1.end);
+            return Ok(Expr(Expr_::Reference(Box::new(expr)), span));
+        });
+        match self.next_token() {
+            // a `list(...)` within a short array can only be valid if it turns out to be a destructuring
+            Some(&TokenSpan(Token::List, _)) => self.parse_destructuring(),
+            Some(&TokenSpan(Token::SquareBracketOpen, _)) if is_list => self.parse_destructuring(),
+            // keep a directly nested short array undecided, it's converted with the enclosing one
+            Some(&TokenSpan(Token::SquareBracketOpen, _)) => self.parse_unary_expression(Precedence::None),
+            _ => self.parse_expression(Precedence::None),
+        }
+    }
+
+    /// parse a `list(...)` or `[...]` destructuring
+    fn parse_destructuring(&mut self) -> Result<Expr, ParserError> {
+        if_lookahead!(self, Token::List, token, {
+            if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
+            let pairs = try!(self.parse_array_pair_list(true));
+            let end_pos = if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose, token, token.1.end);
+            return Ok(Expr(Expr_::List(pairs, false), mk_span(token.1.start, end_pos)));
+        });
+        if_lookahead_expect!(self, Token::SquareBracketOpen, Token::SquareBracketOpen, token, {
+            let pairs = try!(self.parse_array_pair_list(true));
+            let end_pos = if_lookahead_expect!(self, Token::SquareBracketClose, Token::SquareBracketClose, token, token.1.end);
+            Ok(Expr(Expr_::List(pairs, true), mk_span(token.1.start, end_pos)))
+        })
+    }
+
     fn parse_foreach_variable(&mut self) -> Result<Expr, ParserError> {
+        let list = match self.next_token().cloned() {
+            Some(TokenSpan(Token::List, _)) => Some(try!(self.parse_destructuring())),
+            Some(TokenSpan(Token::SquareBracketOpen, span)) => {
+                self.advance(1);
+                Some(try!(self.parse_short_array(span.start)))
+            }
+            _ => None,
+        };
+        if let Some(list) = list {
+            try!(check_destructuring(&list, self.pos));
+            try!(check_write_context(&list, self.pos));
+            return Ok(list);
+        }
         let is_var = self.parse_is_ref();
         let expr = try!(self.parse_variable(false, None));
+        if is_var {
+            try!(check_reference_context(&expr, self.pos));
+        } else {
+            try!(check_write_context(&expr, self.pos));
+        }
         if is_var {
             let span = mk_span(expr.1.start - 1, expr.1.start);
             return Ok(Expr(Expr_::Reference(Box::new(expr)), span));
@@ -1359,6 +2063,25 @@ impl Parser {
     }
 
     #[inline]
+    /// parse the remainder of an alternative syntax block (after the ':'), terminated by `end_token ;`
+    fn parse_alternative_block(&mut self, end_token: Token) -> Result<Block, ParserError> {
+        let (body, stmts_err) = self.parse_inner_statement_list();
+        match self.next_token() {
+            Some(&TokenSpan(ref token, _)) if *token == end_token => (),
+            _ => return Err(stmts_err.unwrap_or_else(|| ParserError::new(vec![end_token], self.pos))),
+        }
+        self.advance(1);
+        if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon);
+        Ok(Block(body))
+    }
+
+    /// parse the body of a control structure, either a statement or `':' inner_statement_list end_token ';'`
+    fn parse_control_block(&mut self, end_token: Token) -> Result<(Block, BlockSyntax), ParserError> {
+        if_lookahead!(self, Token::Colon, _tok, {
+            Ok((try!(self.parse_alternative_block(end_token)), BlockSyntax::Alternative))
+        }, Ok((try!(self.parse_statement_extract_block()).0, BlockSyntax::Normal)))
+    }
+
     fn parse_statement_extract_block(&mut self) -> Result<(Block, Span), ParserError> {
         Ok(match try!(self.parse_statement()) {
             Stmt(Stmt_::Block(bl), span) => (bl, span),
@@ -1425,8 +2148,10 @@ impl Parser {
             loop {
                 if_lookahead!(self, Token::Catch, _tok, {
                     if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
-                    // TODO: support | syntax
-                    let ty = try!(self.parse_name()).0;
+                    let mut types = vec![try!(self.parse_name()).0];
+                    loop {
+                        if_lookahead!(self, Token::BwOr, _tok, types.push(try!(self.parse_name()).0), break);
+                    }
                     let var_binding = if_lookahead_expect!(self, Token::Variable(_), Token::Variable(self.interner.intern("")), tok, match tok.0 {
                         Token::Variable(varname) => varname,
                         _ => unreachable!(),
@@ -1437,7 +2162,7 @@ impl Parser {
                     if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, _tok, (), if let Some(err) = stmts_err {
                         return Err(err)
                     });
-                    catch_clauses.push(CatchClause { ty: ty, var: var_binding, block: Block(block) });
+                    catch_clauses.push(CatchClause { types: types, var: var_binding, block: Block(block) });
                 }, break);
             }
             // parse finally clause (optional)
@@ -1457,7 +2182,10 @@ impl Parser {
             if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
             let mut vars = vec![];
             loop {
-                vars.push(try!(self.parse_variable(false, None)));
+                let var = try!(self.parse_variable(false, None));
+                let var = try!(array_from_short_array(var, self.pos));
+                try!(check_write_context(&var, self.pos));
+                vars.push(var);
                 if_lookahead!(self, Token::Comma, _tok, continue, break);
             }
             if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
@@ -1475,9 +2203,9 @@ impl Parser {
                 { (None, key_or_v) }
             );
             if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
-            let (body, bl_span) = try!(self.parse_statement_extract_block());
-            let span = mk_span(token.1.start, bl_span.end);
-            return Ok(Stmt(Stmt_::ForEach(Box::new(expr), key, value, body), span));
+            let (body, syntax) = try!(self.parse_control_block(Token::EndForeach));
+            let span = mk_span(token.1.start, self.tokens[self.pos - 1].1.end);
+            return Ok(Stmt(Stmt_::ForEach(Box::new(expr), key, value, body, syntax), span));
         });
         // parse a for statement
         if_lookahead!(self, Token::For, token, {
@@ -1490,17 +2218,18 @@ impl Parser {
                 i < stmts.len()
             } { if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon) }
             if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
-            let (block, bl_span) = try!(self.parse_statement_extract_block());
-            let span = mk_span(token.1.start, bl_span.end);
+            let (block, syntax) = try!(self.parse_control_block(Token::Endfor));
+            let span = mk_span(token.1.start, self.tokens[self.pos - 1].1.end);
             let (initial, cond, looper) = (mem::replace(&mut stmts[0], None) , mem::replace(&mut stmts[1], None), mem::replace(&mut stmts[2], None));
-            return Ok(Stmt(Stmt_::For(initial, cond, looper, block), span));
+            return Ok(Stmt(Stmt_::For(initial, cond, looper, block, syntax), span));
         });
         // parse a switch statement
         if_lookahead!(self, Token::Switch, token, {
             if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
             let expr = try!(self.parse_expression(Precedence::None));
             if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
-            if_lookahead_expect!(self, Token::CurlyBracesOpen, Token::CurlyBracesOpen);
+            let syntax = if_lookahead!(self, Token::Colon, _tok, BlockSyntax::Alternative,
+                if_lookahead_expect!(self, Token::CurlyBracesOpen, Token::CurlyBracesOpen, _tok, BlockSyntax::Normal));
             let (mut cases, mut conds) = (vec![], vec![]);
             loop {
                 let case_expr = if_lookahead!(self, Token::Case, _tok, {
@@ -1525,9 +2254,36 @@ impl Parser {
             for cond in conds.into_iter() {
                 cases.push(SwitchCase { default: false, conds: vec![cond], block: Block(vec![]) })
             }
-            let end_pos = if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose, token, token.1.end);
-            let span = mk_span(token.1.start, end_pos);
-            return Ok(Stmt(Stmt_::Switch(Box::new(expr), cases), span));
+            match syntax {
+                BlockSyntax::Normal => if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose),
+                BlockSyntax::Alternative => {
+                    if_lookahead_expect!(self, Token::EndSwitch, Token::EndSwitch);
+                    if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon);
+                }
+            }
+            let span = mk_span(token.1.start, self.tokens[self.pos - 1].1.end);
+            return Ok(Stmt(Stmt_::Switch(Box::new(expr), cases, syntax), span));
+        });
+        // declare(directives) statement | declare(directives): inner_statement_list enddeclare;
+        if_lookahead!(self, Token::Declare, token, {
+            if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
+            let mut directives = vec![];
+            loop {
+                let name = if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, match token.0 {
+                    Token::String(name) => name,
+                    _ => unreachable!(),
+                });
+                if_lookahead_expect!(self, Token::Equal, Token::Equal);
+                directives.push((name, try!(self.parse_expression(Precedence::None))));
+                if_lookahead!(self, Token::Comma, _tok, continue, break);
+            }
+            if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
+            let (body, syntax) = if_lookahead!(self, Token::SemiColon, _tok, (None, BlockSyntax::Normal), {
+                let (body, syntax) = try!(self.parse_control_block(Token::EndDeclare));
+                (Some(body), syntax)
+            });
+            let span = mk_span(token.1.start, self.tokens[self.pos - 1].1.end);
+            return Ok(Stmt(Stmt_::Declare(directives, body, syntax), span));
         });
         // parse an if/while-statement/do-while
         match self.next_token().map(|x| x.0.clone()) {
@@ -1537,6 +2293,7 @@ impl Parser {
                 let token = self.next_token().unwrap().clone();
                 self.advance(1);
                 let mut stmts = vec![];
+                let mut syntax = BlockSyntax::Normal;
                 let do_while_body = match token.0 {
                     Token::Do => {
                         let (ret, _) = try!(self.parse_statement_extract_block());
@@ -1570,37 +2327,69 @@ impl Parser {
                         return Ok(Stmt(Stmt_::DoWhile(do_while_body.unwrap(), Box::new(cond_expr.unwrap())), span));
                     }
 
-                    let (if_body, if_span) = try!(self.parse_statement_extract_block());
-                    let span = mk_span(start_pos, if_span.end);
-
                     if let Token::While = token.0 {
-                        return Ok(Stmt(Stmt_::While(Box::new(cond_expr.unwrap()), if_body), span));
+                        let (body, syntax) = try!(self.parse_control_block(Token::EndWhile));
+                        let span = mk_span(start_pos, self.tokens[self.pos - 1].1.end);
+                        return Ok(Stmt(Stmt_::While(Box::new(cond_expr.unwrap()), body, syntax), span));
+                    }
+
+                    // the syntax of the initial if determines the syntax of all elseif/else branches
+                    if stmts.is_empty() {
+                        syntax = if_lookahead!(self, Token::Colon, _tok, BlockSyntax::Alternative, BlockSyntax::Normal);
+                    } else if let BlockSyntax::Alternative = syntax {
+                        if_lookahead_expect!(self, Token::Colon, Token::Colon);
                     }
+                    let if_body = match syntax {
+                        BlockSyntax::Normal => try!(self.parse_statement_extract_block()).0,
+                        BlockSyntax::Alternative => {
+                            let (body, stmts_err) = self.parse_inner_statement_list();
+                            match self.next_token() {
+                                Some(&TokenSpan(Token::ElseIf, _)) | Some(&TokenSpan(Token::Else, _)) |
+                                Some(&TokenSpan(Token::EndIf, _)) => (),
+                                _ => return Err(stmts_err.unwrap_or_else(|| ParserError::new(vec![Token::EndIf], self.pos))),
+                            }
+                            Block(body)
+                        }
+                    };
+                    let span = mk_span(start_pos, self.tokens[self.pos - 1].1.end);
 
                     if let Some(cond_expr) = cond_expr {
-                        stmts.push(Stmt(Stmt_::If(Box::new(cond_expr), if_body, Block::empty()),
+                        stmts.push(Stmt(Stmt_::If(Box::new(cond_expr), if_body, Block::empty(), syntax),
                                         span));
                     } else {
                         stmts.push(Stmt(Stmt_::Block(if_body), span));
+                        // else is always the last branch
+                        break;
                     }
                 }
+                let end_pos = match syntax {
+                    BlockSyntax::Normal => None,
+                    BlockSyntax::Alternative => {
+                        if_lookahead_expect!(self, Token::EndIf, Token::EndIf);
+                        Some(if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon, token, token.1.end))
+                    }
+                };
                 let initial_stmt = stmts.pop().unwrap();
-                return Ok(stmts.into_iter().rev().fold(initial_stmt, |acc, el| match (acc, el) {
+                let mut if_stmt = stmts.into_iter().rev().fold(initial_stmt, |acc, el| match (acc, el) {
                     (Stmt(Stmt_::Block(e2), bl_span),
-                     Stmt(Stmt_::If(cond, bl, else_bl), mut span)) => {
+                     Stmt(Stmt_::If(cond, bl, else_bl, syntax), mut span)) => {
                         assert_eq!(else_bl.0.len(), 0);
                         span.end = bl_span.end;
-                        Stmt(Stmt_::If(cond, bl, e2), span)
+                        Stmt(Stmt_::If(cond, bl, e2, syntax), span)
                     }
-                    (Stmt(Stmt_::If(cond, bl, else_bl), span),
-                     Stmt(Stmt_::If(cond2, bl2, mut else_bl2), mut span2)) => {
+                    (Stmt(Stmt_::If(cond, bl, else_bl, syntax), span),
+                     Stmt(Stmt_::If(cond2, bl2, mut else_bl2, syntax2), mut span2)) => {
                         assert_eq!(else_bl2.0.len(), 0);
                         span2.end = span.end;
-                        else_bl2.0.push(Stmt(Stmt_::If(cond, bl, else_bl), span));
-                        Stmt(Stmt_::If(cond2, bl2, else_bl2), span2)
+                        else_bl2.0.push(Stmt(Stmt_::If(cond, bl, else_bl, syntax), span));
+                        Stmt(Stmt_::If(cond2, bl2, else_bl2, syntax2), span2)
                     }
                     _ => unreachable!(),
-                }));
+                });
+                if let Some(end_pos) = end_pos {
+                    if_stmt.1.end = end_pos;
+                }
+                return Ok(if_stmt);
             }
             _ => (),
         }
@@ -1610,11 +2399,70 @@ impl Parser {
         if_lookahead_restore!(self, Token::Global, token, {
             deepest!(deepest_err, self.parse_global_var_decl(&token.1));
         });
+        // attributed function, class, interface, trait or enum declaration
+        if let Some(&TokenSpan(Token::AttributeStart, ref span)) = self.next_token() {
+            let (old_pos, start) = (self.pos, span.start);
+            let attributes = try!(self.parse_attributes());
+            let decl = match self.next_token().cloned() {
+                Some(TokenSpan(Token::Function, span)) => {
+                    self.advance(1);
+                    self.parse_function_declaration(span, false, false)
+                }
+                Some(TokenSpan(Token::Class, _)) |
+                Some(TokenSpan(Token::Abstract, _)) |
+                Some(TokenSpan(Token::Final, _)) |
+                Some(TokenSpan(Token::Interface, _)) |
+                Some(TokenSpan(Token::Trait, _)) => self.parse_oo_declaration(),
+                Some(_) if self.is_enum_declaration() => self.parse_oo_declaration(),
+                _ => Err(ParserError::new(vec![Token::Function, Token::Class, Token::Interface, Token::Trait], self.pos)),
+            };
+            match decl {
+                Ok(Stmt(Stmt_::Decl(Decl::GlobalFunction(name, mut decl)), span)) => {
+                    decl.attributes = attributes;
+                    return Ok(Stmt(Stmt_::Decl(Decl::GlobalFunction(name, decl)), mk_span(start, span.end)));
+                }
+                Ok(Stmt(Stmt_::Decl(Decl::Class(mut decl)), span)) => {
+                    decl.attributes = attributes;
+                    return Ok(Stmt(Stmt_::Decl(Decl::Class(decl)), mk_span(start, span.end)));
+                }
+                Ok(Stmt(Stmt_::Decl(Decl::Interface(_, name, implements, members)), span)) => {
+                    return Ok(Stmt(Stmt_::Decl(Decl::Interface(attributes, name, implements, members)), mk_span(start, span.end)));
+                }
+                Ok(Stmt(Stmt_::Decl(Decl::Trait(_, name, members)), span)) => {
+                    return Ok(Stmt(Stmt_::Decl(Decl::Trait(attributes, name, members)), mk_span(start, span.end)));
+                }
+                Ok(Stmt(Stmt_::Decl(Decl::Enum(mut decl)), span)) => {
+                    decl.attributes = attributes;
+                    return Ok(Stmt(Stmt_::Decl(Decl::Enum(decl)), mk_span(start, span.end)));
+                }
+                decl => {
+                    // might still be an attributed closure, which is handled as expression
+                    deepest!(deepest_err, decl);
+                    self.pos = old_pos;
+                }
+            }
+        }
         // function declaration statement
         if_lookahead_restore!(self, Token::Function, token, {
             deepest!(deepest_err, self.parse_function_declaration(token.1, false, false));
         });
+        // `enum Name` can't start any other statement
+        if self.is_enum_declaration() {
+            return self.parse_oo_declaration();
+        }
+        let old_pos = self.pos;
         deepest!(deepest_err, self.parse_oo_declaration());
+        self.pos = old_pos;
+
+        // label: identifier ':'
+        match (self.tokens.get(self.pos).cloned(), self.tokens.get(self.pos + 1).map(|x| x.0.clone())) {
+            (Some(TokenSpan(Token::String(name), span)), Some(Token::Colon)) => {
+                self.advance(2);
+                let span = mk_span(span.start, self.tokens[self.pos - 1].1.end);
+                return Ok(Stmt(Stmt_::Label(name), span));
+            }
+            _ => (),
+        }
 
         // parse other statements
         deepest!(deepest_err, match self.next_token().cloned() {
@@ -1626,6 +2474,10 @@ impl Parser {
                     Token::Continue => Some(Stmt_::Continue(try!(self.parse_opt_expression(Precedence::None)).map(Box::new))),
                     Token::Break => Some(Stmt_::Break(try!(self.parse_opt_expression(Precedence::None)).map(Box::new))),
                     Token::Throw => Some(Stmt_::Throw(Box::new(try!(self.parse_expression(Precedence::None))))),
+                    Token::Goto => Some(Stmt_::Goto(if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, match token.0 {
+                        Token::String(label) => label,
+                        _ => unreachable!(),
+                    }))),
                     Token::InlineHtml(str_) => Some(Stmt_::Echo(vec![Expr(Expr_::String(str_), span.clone())])),
                     _ => None,
                 };
@@ -1660,6 +2512,9 @@ impl Parser {
     /// this subform is just used to disallow certain constructs in inner scopes
     /// (e.g. not allowing namespace stuff, throwing error for __HALTCOMPILER, etc.)
     fn parse_inner_statement(&mut self) -> Result<Stmt, ParserError> {
+        if let Some(&TokenSpan(Token::HaltCompiler, _)) = self.next_token() {
+            return Err(ParserError::message("__HALT_COMPILER() can only be used from the outermost scope", self.pos));
+        }
         // TODO: incomplete
         self.parse_statement()
     }
@@ -1680,18 +2535,36 @@ impl Parser {
         (stmts, None)
     }
 
-    fn parse_member_modifiers(&mut self) -> Vec<MemberModifier> {
-        let mut modifiers = vec![];
+    /// parse member modifiers (each with its span), which may neither be repeated nor contain multiple visibilities
+    fn parse_member_modifiers(&mut self) -> Result<Vec<(MemberModifier, Span)>, ParserError> {
+        let is_visibility = |m: MemberModifier| m == MemberModifier::Public || m == MemberModifier::Protected || m == MemberModifier::Private;
+        let mut modifiers: Vec<(MemberModifier, Span)> = vec![];
         loop {
-            if_lookahead!(self, Token::Public, _tok, { modifiers.push(MemberModifier::Public); continue });
-            if_lookahead!(self, Token::Protected, _tok, { modifiers.push(MemberModifier::Protected); continue; });
-            if_lookahead!(self, Token::Private, _tok, { modifiers.push(MemberModifier::Private); continue; });
-            if_lookahead!(self, Token::Static, _tok, { modifiers.push(MemberModifier::Static); continue; });
-            if_lookahead!(self, Token::Abstract, _tok, { modifiers.push(MemberModifier::Abstract); continue; });
-            if_lookahead!(self, Token::Final, _tok, { modifiers.push(MemberModifier::Final); continue; });
-            break;
+            let (modifier, span) = match self.next_token() {
+                Some(&TokenSpan(Token::Public, ref span)) => (MemberModifier::Public, span.clone()),
+                Some(&TokenSpan(Token::Protected, ref span)) => (MemberModifier::Protected, span.clone()),
+                Some(&TokenSpan(Token::Private, ref span)) => (MemberModifier::Private, span.clone()),
+                Some(&TokenSpan(Token::Static, ref span)) => (MemberModifier::Static, span.clone()),
+                Some(&TokenSpan(Token::Abstract, ref span)) => (MemberModifier::Abstract, span.clone()),
+                Some(&TokenSpan(Token::Final, ref span)) => (MemberModifier::Final, span.clone()),
+                _ => break,
+            };
+            self.advance(1);
+            let message = if is_visibility(modifier) && modifiers.iter().any(|&(m, _)| is_visibility(m)) {
+                "Multiple access type modifiers are not allowed"
+            } else if modifiers.iter().any(|&(m, _)| m == modifier) {
+                match modifier {
+                    MemberModifier::Static => "Multiple static modifiers are not allowed",
+                    MemberModifier::Abstract => "Multiple abstract modifiers are not allowed",
+                    _ => "Multiple final modifiers are not allowed",
+                }
+            } else {
+                modifiers.push((modifier, span));
+                continue;
+            };
+            return Err(ParserError::syntax(SyntaxError::Invalid(message, span), self.pos));
         }
-        modifiers
+        Ok(modifiers)
     }
 
     fn parse_absolute_trait_method_reference(&mut self) -> Result<(Path, RcStr), ParserError> {
@@ -1703,12 +2576,25 @@ impl Parser {
 
     fn parse_class_statement(&mut self) -> Result<Vec<Member>, ParserError> {
         let mut members = vec![];
-        let (modifiers, is_var) = if_lookahead!(self, Token::Var, _tok, (MemberModifiers::new(&[MemberModifier::Public]), true),
-            (MemberModifiers::new(&self.parse_member_modifiers()), false)
-        );
+        let attributes = try!(self.parse_attributes());
+        // enum case: case name [= value];
+        if_lookahead!(self, Token::Case, _tok, {
+            let name = try!(self.parse_identifier()).0;
+            let value = if_lookahead!(self, Token::Equal, _tok, Some(try!(self.parse_expression(Precedence::None))), None);
+            if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon);
+            return Ok(vec![Member::EnumCase(attributes, name, value)]);
+        });
+        let (modifier_list, is_var) = if_lookahead!(self, Token::Var, _tok, (vec![], true), (try!(self.parse_member_modifiers()), false));
+        let modifiers = if is_var {
+            MemberModifiers::new(&[MemberModifier::Public, MemberModifier::Var])
+        } else {
+            MemberModifiers::new(&modifier_list.iter().map(|&(m, _)| m).collect::<Vec<_>>())
+        };
 
         if !is_var {
-            if_lookahead!(self, Token::Use, _tok, {
+            // trait uses can't have attributes
+            if let (true, Some(&TokenSpan(Token::Use, _))) = (attributes.is_empty(), self.next_token()) {
+                self.advance(1);
                 let names = try!(self.parse_name_list()).into_iter().map(|x| x.0).collect();
                 // trait_adaptions
                 if_lookahead!(self, Token::SemiColon, _tok, {
@@ -1742,21 +2628,38 @@ impl Parser {
                             continue;
                         });
                     }
+                    // trait_alias: T_AS [modifiers] [alias], at least one of both is required
                     if_lookahead!(self, Token::As, _tok, {
-                        if_lookahead!(self, Token::String(_), token, match token.0 {
-                            Token::String(str_) => uses.push(TraitUse::As(path_to_trait, trait_method_name, MemberModifiers::none(), Some(str_))),
-                            _ => unreachable!()
-                        }, unimplemented!());
+                        let modifier_list = try!(self.parse_member_modifiers());
+                        for &(modifier, ref span) in &modifier_list {
+                            let message = match modifier {
+                                MemberModifier::Static => "Cannot use 'static' as method modifier",
+                                MemberModifier::Abstract => "Cannot use 'abstract' as method modifier",
+                                _ => continue,
+                            };
+                            return Err(ParserError::syntax(SyntaxError::Invalid(message, span.clone()), self.pos));
+                        }
+                        let modifiers = MemberModifiers::new(&modifier_list.iter().map(|&(m, _)| m).collect::<Vec<_>>());
+                        let alias = match self.parse_identifier() {
+                            Ok((alias, _)) => Some(alias),
+                            Err(e) => if modifier_list.is_empty() {
+                                return Err(e);
+                            } else {
+                                None
+                            },
+                        };
+                        uses.push(TraitUse::As(path_to_trait, trait_method_name, modifiers, alias));
                     });
                 }
                 if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose);
                 return Ok(vec![Member::TraitUse(names, uses)]);
-            });
+            }
             if_lookahead!(self, Token::Function, token, {
-                let (name, decl) = match try!(self.parse_function_declaration(token.1, false, true)).0 {
+                let (name, mut decl) = match try!(self.parse_function_declaration(token.1, false, true)).0 {
                     Stmt_::Decl(Decl::GlobalFunction(name, decl)) => (name, decl),
                     _ => unreachable!(),
                 };
+                decl.attributes = attributes;
                 members.push(Member::Method(modifiers, name, decl));
                 // function declaration does not require semicolon as constants below, so return early
                 return Ok(members);
@@ -1764,11 +2667,20 @@ impl Parser {
             // constants
             if members.is_empty() {
                 if_lookahead!(self, Token::Const, _tok, {
+                    // static and abstract aren't valid for constants
+                    for &(modifier, ref span) in &modifier_list {
+                        let message = match modifier {
+                            MemberModifier::Static => "Cannot use 'static' as constant modifier",
+                            MemberModifier::Abstract => "Cannot use 'abstract' as constant modifier",
+                            _ => continue,
+                        };
+                        return Err(ParserError::syntax(SyntaxError::Invalid(message, span.clone()), self.pos));
+                    }
                     loop {
                         let id = try!(self.parse_identifier()).0;
                         if_lookahead_expect!(self, Token::Equal, Token::Equal);
                         let val = try!(self.parse_expression(Precedence::None));
-                        members.push(Member::Constant(modifiers, id, val));
+                        members.push(Member::Constant(attributes.clone(), modifiers, id, val));
                         if_lookahead!(self, Token::Comma, _tok, continue, break);
                     }
                 });
@@ -1777,13 +2689,30 @@ impl Parser {
 
         // properties
         if members.is_empty() {
+            let ty = match self.next_token().cloned() {
+                Some(TokenSpan(Token::Variable(_), _)) => None,
+                Some(TokenSpan(_, span)) => {
+                    let ty = try!(self.parse_nullable_type());
+                    let is_callable = match ty.0 {
+                        Ty::Callable => true,
+                        Ty::Union(ref types) => types.contains(&Ty::Callable),
+                        _ => false,
+                    };
+                    if is_callable {
+                        let span = mk_span(span.start, self.tokens[self.pos - 1].1.end);
+                        return Err(ParserError::syntax(SyntaxError::Invalid("Property cannot have type callable", span), self.pos));
+                    }
+                    Some(ty)
+                }
+                None => return Err(ParserError::new(vec![Token::Variable(self.interner.intern(""))], self.pos)),
+            };
             loop {
                 let varname = if_lookahead_expect!(self, Token::Variable(_), Token::Variable(self.interner.intern("")), token, match token.0 {
                     Token::Variable(var) => var,
                     _ => unreachable!(),
                 });
                 let default_val = if_lookahead!(self, Token::Equal, _tok, Some(try!(self.parse_expression(Precedence::None))), None);
-                members.push(Member::Property(modifiers, varname, default_val));
+                members.push(Member::Property(attributes.clone(), modifiers, ty.clone(), varname, default_val));
                 if_lookahead!(self, Token::Comma, _tok, continue, break);
             }
         }
@@ -1805,28 +2734,145 @@ impl Parser {
         (exprs, None)
     }
 
+    /// whether the next tokens start an enum declaration, `enum` is no reserved word so it's only a keyword when a name follows
+    fn is_enum_declaration(&self) -> bool {
+        match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1).map(|x| &x.0)) {
+            (Some(&TokenSpan(Token::String(ref keyword), _)), Some(&Token::String(_))) => {
+                (keyword.borrow() as &str).eq_ignore_ascii_case("enum")
+            }
+            _ => false,
+        }
+    }
+
+    /// whether the next tokens start a namespace declaration (and not a relative name like namespace\foo)
+    fn is_namespace_declaration(&self) -> bool {
+        match (self.tokens.get(self.pos).map(|x| &x.0), self.tokens.get(self.pos + 1).map(|x| &x.0)) {
+            (Some(&Token::Namespace), Some(&Token::NsSeparator)) => false,
+            (Some(&Token::Namespace), _) => true,
+            _ => false,
+        }
+    }
+
+    /// namespace name; top_statement_list | namespace [name] { top_statement_list }
+    fn parse_namespace_declaration(&mut self) -> Result<Stmt, ParserError> {
+        let start_pos = if_lookahead_expect!(self, Token::Namespace, Token::Namespace, token, token.1.start);
+        let name = match self.next_token() {
+            Some(&TokenSpan(Token::String(_), _)) => Some(try!(self.parse_namespace_name()).0),
+            _ => None,
+        };
+        let (stmts, braced) = if_lookahead!(self, Token::CurlyBracesOpen, _tok, {
+            let mut stmts = vec![];
+            loop {
+                if_lookahead!(self, Token::CurlyBracesClose, _tok, break);
+                if self.is_namespace_declaration() {
+                    return Err(ParserError::message("Namespace declarations cannot be nested", self.pos));
+                }
+                if self.next_token().is_none() {
+                    return Err(ParserError::new(vec![Token::CurlyBracesClose], self.pos));
+                }
+                stmts.push(try!(self.parse_top_statement()));
+            }
+            (stmts, true)
+        }, {
+            // only the braced syntax allows to omit the name (global namespace)
+            if name.is_none() {
+                return Err(ParserError::new(vec![Token::String(self.interner.intern("")), Token::CurlyBracesOpen], self.pos));
+            }
+            if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon);
+            // all statements up to the next namespace declaration belong to this namespace
+            let mut stmts = vec![];
+            while self.next_token().is_some() && !self.is_namespace_declaration() {
+                stmts.push(try!(self.parse_top_statement()));
+            }
+            (stmts, false)
+        });
+        let span = mk_span(start_pos, self.tokens[self.pos - 1].1.end);
+        Ok(Stmt(Stmt_::Decl(Decl::Namespace(name, Block(stmts), braced)), span))
+    }
+
+    /// parse the optional kind of a use clause (`function` or `const`)
+    fn parse_use_kind(&mut self) -> Option<UseKind> {
+        if_lookahead!(self, Token::Function, _tok, Some(UseKind::Function), if_lookahead!(self, Token::Const, _tok, Some(UseKind::Const), None))
+    }
+
+    fn parse_use_alias(&mut self) -> Result<Option<RcStr>, ParserError> {
+        Ok(if_lookahead!(self, Token::As, _tok, {
+            if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, {
+                match token.0 {
+                    Token::String(str_) => Some(str_),
+                    _ => unreachable!(),
+                }
+            })
+        }, None))
+    }
+
     fn parse_top_statement(&mut self) -> Result<Stmt, ParserError> {
-        if_lookahead!(self, Token::Namespace, token, {
-            let name = try!(self.parse_namespace_name());
-            let end_pos = if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon, token, token.1.start);
-            let span = mk_span(token.1.start, end_pos);
-            return Ok(Stmt(Stmt_::Decl(Decl::Namespace(name.0)), span));
+        if self.is_namespace_declaration() {
+            return self.parse_namespace_declaration();
+        }
+        // const name = expr, ...;
+        if_lookahead!(self, Token::Const, token, {
+            let mut constants = vec![];
+            loop {
+                let name = if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, match token.0 {
+                    Token::String(name) => name,
+                    _ => unreachable!(),
+                });
+                if_lookahead_expect!(self, Token::Equal, Token::Equal);
+                constants.push((name, try!(self.parse_expression(Precedence::None))));
+                if_lookahead!(self, Token::Comma, _tok, continue, break);
+            }
+            let end_pos = if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon, token, token.1.end);
+            return Ok(Stmt(Stmt_::Decl(Decl::GlobalConstants(constants)), mk_span(token.1.start, end_pos)));
+        });
+        // __halt_compiler(); followed by the remaining data of the file
+        if_lookahead!(self, Token::HaltCompiler, token, {
+            if_lookahead_expect!(self, Token::ParenthesesOpen, Token::ParenthesesOpen);
+            if_lookahead_expect!(self, Token::ParenthesesClose, Token::ParenthesesClose);
+            // the semicolon is optional, since a close tag (which is stripped) terminates the statement as well
+            if_lookahead!(self, Token::SemiColon, _tok, {});
+            let end_pos = self.tokens[self.pos - 1].1.end;
+            let data = if_lookahead_expect!(self, Token::HaltCompilerData(_), Token::SemiColon, token, match token.0 {
+                Token::HaltCompilerData(data) => data,
+                _ => unreachable!(),
+            });
+            return Ok(Stmt(Stmt_::HaltCompiler(data), mk_span(token.1.start, end_pos)));
         });
         if_lookahead!(self, Token::Use, token, {
+            let kind = self.parse_use_kind();
             let mut clauses = vec![];
             loop {
                 let is_fqdn = if_lookahead!(self, Token::NsSeparator, _tok, true, false);
                 let mut ns_name = try!(self.parse_namespace_name()).0;
-                ns_name.is_absolute = is_fqdn;
-                let alias = if_lookahead!(self, Token::As, _tok, {
-                    if_lookahead_expect!(self, Token::String(_), Token::String(self.interner.intern("")), token, {
-                        match token.0 {
-                            Token::String(str_) => Some(str_),
-                            _ => unreachable!(),
-                        }
-                    })
-                }, None);
-                clauses.push(UseClause::QualifiedName(ns_name, alias));
+                if is_fqdn {
+                    ns_name.kind = PathKind::FullyQualified;
+                }
+                // group use: prefix\{ [kind] name [as alias], ... }
+                if let (Some(&TokenSpan(Token::NsSeparator, _)), Some(&TokenSpan(Token::CurlyBracesOpen, _))) = (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+                    self.advance(2);
+                    let mut group = vec![];
+                    loop {
+                        // allow trailing commas
+                        if_lookahead!(self, Token::CurlyBracesClose, _tok, break);
+                        // the kind can only be specified per clause if none was given for the whole group
+                        let clause_kind = match kind {
+                            Some(kind) => kind,
+                            None => self.parse_use_kind().unwrap_or(UseKind::Class),
+                        };
+                        let name = try!(self.parse_namespace_name()).0;
+                        group.push(UseClause::QualifiedName(clause_kind, name, try!(self.parse_use_alias())));
+                        if_lookahead!(self, Token::Comma, _tok, continue, {
+                            if_lookahead_expect!(self, Token::CurlyBracesClose, Token::CurlyBracesClose);
+                            break;
+                        });
+                    }
+                    if group.is_empty() {
+                        return Err(ParserError::new(vec![Token::String(self.interner.intern(""))], self.pos - 1));
+                    }
+                    clauses.push(UseClause::Group(ns_name, group));
+                } else {
+                    clauses.push(UseClause::QualifiedName(kind.unwrap_or(UseKind::Class), ns_name, try!(self.parse_use_alias())));
+                }
                 if_lookahead!(self, Token::Comma, _tok, continue, break);
             }
             let end_pos = if_lookahead_expect!(self, Token::SemiColon, Token::SemiColon, token, token.1.end);
@@ -1840,12 +2886,55 @@ impl Parser {
     fn parse_top_statement_list(&mut self) -> Result<Vec<Stmt>, ParserError> {
         let mut stmts = vec![];
         let tokc = self.tokens.len() - 1;
+        // whether the namespaces use the braced syntax (if any namespace was declared yet)
+        let mut braced_namespaces = None;
+        let mut has_code = false;
         while self.pos <= tokc {
-            stmts.push(try!(self.parse_top_statement()));
+            let stmt_pos = self.pos;
+            let stmt = try!(self.parse_top_statement());
+            match stmt.0 {
+                Stmt_::Decl(Decl::Namespace(_, _, braced)) => {
+                    if has_code {
+                        return Err(ParserError::message("Namespace declaration statement has to be the very first statement or after any declare call in the script", stmt_pos));
+                    }
+                    match braced_namespaces {
+                        Some(other) if other != braced => {
+                            return Err(ParserError::message("Cannot mix bracketed namespace declarations with unbracketed namespace declarations", stmt_pos));
+                        }
+                        _ => braced_namespaces = Some(braced),
+                    }
+                }
+                Stmt_::Declare(_, _, _) | Stmt_::HaltCompiler(_) => (),
+                _ => {
+                    if let Some(true) = braced_namespaces {
+                        return Err(ParserError::message("No code may exist outside of namespace {}", stmt_pos));
+                    }
+                    has_code = true;
+                }
+            }
+            stmts.push(stmt);
         }
+        try!(self.check_goto_targets(&stmts));
         Ok(stmts)
     }
 
+    /// ensure that no goto within the given scope (a function body or the top-level) jumps into a loop or switch
+    fn check_goto_targets(&self, stmts: &[Stmt]) -> Result<(), ParserError> {
+        let (mut labels, mut gotos) = (vec![], vec![]);
+        collect_goto_labels(stmts, &mut vec![], &mut labels, &mut gotos);
+        for &(ref name, ref path, ref span) in &gotos {
+            // the label is only reachable if all loops/switches containing it also contain the goto
+            let into_loop = labels.iter().any(|&(ref label, ref label_path)| {
+                label == name && (label_path.len() > path.len() || label_path[..] != path[..label_path.len()])
+            });
+            if into_loop {
+                let pos = self.tokens.iter().position(|x| x.1.start == span.start).unwrap_or(self.pos);
+                return Err(ParserError::message("'goto' into loop or switch statement is disallowed", pos));
+            }
+        }
+        Ok(())
+    }
+
     fn parse_tokens(interner: Interner,
                     ext: TokenizerExternalState,
                     toks: Vec<TokenSpan>)
@@ -1878,7 +2967,10 @@ impl Parser {
                     (p.tokens.len() - 1, true)
                 };
                 let span = p.tokens[pos].1.clone();
-                let (start, end) = if after {
+                let (start, end) = if let Some(ref syntax) = e.syntax {
+                    let span = syntax.span();
+                    (span.start, span.end)
+                } else if after {
                     (span.end, span.end + 1)
                 } else {
                     (span.start, span.end)
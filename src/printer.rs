@@ -2,9 +2,9 @@
 use std::fmt::{self, Write};
 use std::borrow::Borrow;
 use tokens::Token;
-use ast::{Block, Const, ClassModifiers, ClassModifier, Decl, FunctionDecl, Stmt, Stmt_, Expr,
-          Expr_, IncludeTy, Op, Path, UnaryOp, Ty, TraitUse, UseClause};
-use ast::{Member, MemberModifiers, MemberModifier, Variable};
+use ast::{Argument, AttributeGroup, Block, BlockSyntax, CastTy, Const, ClassModifiers, ClassModifier, Decl, FunctionDecl, Stmt, Stmt_, Expr,
+          Expr_, IncludeTy, Op, Path, PathKind, UnaryOp, Ty, NullableTy, TraitUse, UseClause, UseKind};
+use ast::{Member, MemberModifiers, MemberModifier, ParamDefinition, Variable};
 
 pub struct PrettyPrinter<W: Write> {
     indentation: usize,
@@ -53,6 +53,74 @@ impl<W: Write> PrettyPrinter<W> {
         Ok(())
     }
 
+    fn print_call_arguments(&mut self, args: &[Argument]) -> fmt::Result {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                try!(self.write(", "));
+            }
+            if let Some(ref name) = arg.name {
+                try!(self.write(name.borrow()));
+                try!(self.write(": "));
+            }
+            if arg.unpack {
+                try!(self.write("..."));
+            }
+            if arg.by_ref {
+                try!(self.write("&"));
+            }
+            try!(self.print_expression(&arg.value));
+        }
+        Ok(())
+    }
+
+    fn print_attribute_group(&mut self, group: &AttributeGroup) -> fmt::Result {
+        try!(self.write("#["));
+        for (i, attribute) in group.0.iter().enumerate() {
+            if i > 0 {
+                try!(self.write(", "));
+            }
+            try!(write!(self.target, "{}", attribute.name));
+            if !attribute.args.is_empty() {
+                try!(self.write("("));
+                try!(self.print_call_arguments(&attribute.args));
+                try!(self.write(")"));
+            }
+        }
+        self.write("]")
+    }
+
+    /// print attribute groups each on their own line, in front of a declaration
+    fn print_attribute_lines(&mut self, groups: &[AttributeGroup]) -> fmt::Result {
+        for group in groups {
+            try!(self.write_indented(""));
+            try!(self.print_attribute_group(group));
+            try!(self.write("\n"));
+        }
+        Ok(())
+    }
+
+    /// print attribute groups in front of a parameter or closure
+    fn print_attributes_inline(&mut self, groups: &[AttributeGroup]) -> fmt::Result {
+        for group in groups {
+            try!(self.print_attribute_group(group));
+            try!(self.write(" "));
+        }
+        Ok(())
+    }
+
+    /// print the contents of a double-quoted (or backtick) string, bytes which aren't printable ASCII are hex-escaped
+    fn print_escaped_bytes(&mut self, bytes: &[u8], quote: char) -> fmt::Result {
+        for &byte in bytes {
+            match byte as char {
+                '\\' | '$' => try!(write!(self.target, "\\{}", byte as char)),
+                ch if ch == quote => try!(write!(self.target, "\\{}", ch)),
+                ' '...'~' => try!(write!(self.target, "{}", byte as char)),
+                _ => try!(write!(self.target, "\\x{:02X}", byte)),
+            }
+        }
+        Ok(())
+    }
+
     fn print_member_body(&mut self, members: &[Member]) -> fmt::Result {
         try!(self.write(" {\n"));
         self.indentation += 1;
@@ -66,27 +134,49 @@ impl<W: Write> PrettyPrinter<W> {
 
     fn print_decl(&mut self, decl: &Decl) -> fmt::Result {
         match *decl {
-            Decl::Namespace(ref path) => {
-                try!(self.write_indented("namespace "));
-                try!(write!(self.target, "{}", path));
-                self.write(";\n")
+            Decl::Namespace(ref path, ref block, braced) => {
+                try!(self.write_indented("namespace"));
+                if let Some(ref path) = *path {
+                    try!(write!(self.target, " {}", path));
+                }
+                if braced {
+                    try!(self.write(" "));
+                    self.print_block(block)
+                } else {
+                    try!(self.write(";\n"));
+                    for stmt in &block.0 {
+                        try!(self.print_statement(stmt));
+                    }
+                    Ok(())
+                }
             }
             Decl::GlobalFunction(ref name, ref decl) => {
+                try!(self.print_attribute_lines(&decl.attributes));
                 try!(self.write_indented(""));
                 self.print_function(decl, Some(name.borrow()))
             }
+            Decl::GlobalConstants(ref constants) => {
+                try!(self.write_indented("const "));
+                for (i, &(ref name, ref value)) in constants.iter().enumerate() {
+                    if i > 0 {
+                        try!(self.write(", "));
+                    }
+                    try!(write!(self.target, "{} = ", name.borrow() as &str));
+                    try!(self.print_expression(value));
+                }
+                self.write(";\n")
+            }
             Decl::Class(ref classdecl) => {
+                try!(self.print_attribute_lines(&classdecl.attributes));
                 try!(self.write_indented(""));
                 try!(write!(self.target, "{}", classdecl.cmod));
                 try!(self.write("class "));
                 try!(self.write(classdecl.name.borrow()));
-                try!(self.write(" "));
                 if let Some(ref base_class) = classdecl.base_class {
-                    try!(self.write("extends "));
-                    try!(write!(self.target, "{} ", base_class));
+                    try!(write!(self.target, " extends {}", base_class));
                 }
                 if !classdecl.implements.is_empty() {
-                    try!(self.write("implements "));
+                    try!(self.write(" implements "));
                     for (i, iface) in classdecl.implements.iter().enumerate() {
                         if i > 0 {
                             try!(self.write(", "));
@@ -94,9 +184,11 @@ impl<W: Write> PrettyPrinter<W> {
                         try!(write!(self.target, "{}", iface));
                     }
                 }
-                self.print_member_body(&classdecl.members)
+                try!(self.print_member_body(&classdecl.members));
+                self.write("\n")
             }
-            Decl::Interface(ref name, ref implements, ref members) => {
+            Decl::Interface(ref attributes, ref name, ref implements, ref members) => {
+                try!(self.print_attribute_lines(attributes));
                 try!(self.write_indented("interface "));
                 try!(self.write(name.borrow()));
                 if !implements.is_empty() {
@@ -108,12 +200,34 @@ impl<W: Write> PrettyPrinter<W> {
                         try!(write!(self.target, "{}", iface));
                     }
                 }
-                self.print_member_body(members)
+                try!(self.print_member_body(members));
+                self.write("\n")
             }
-            Decl::Trait(ref name, ref members) => {
+            Decl::Trait(ref attributes, ref name, ref members) => {
+                try!(self.print_attribute_lines(attributes));
                 try!(self.write_indented("trait "));
                 try!(self.write(name.borrow()));
-                self.print_member_body(members)
+                try!(self.print_member_body(members));
+                self.write("\n")
+            }
+            Decl::Enum(ref enumdecl) => {
+                try!(self.print_attribute_lines(&enumdecl.attributes));
+                try!(self.write_indented("enum "));
+                try!(self.write(enumdecl.name.borrow()));
+                if let Some(ref ty) = enumdecl.backing_ty {
+                    try!(write!(self.target, ": {}", ty));
+                }
+                if !enumdecl.implements.is_empty() {
+                    try!(self.write(" implements "));
+                    for (i, iface) in enumdecl.implements.iter().enumerate() {
+                        if i > 0 {
+                            try!(self.write(", "));
+                        }
+                        try!(write!(self.target, "{}", iface));
+                    }
+                }
+                try!(self.print_member_body(&enumdecl.members));
+                self.write("\n")
             }
             Decl::StaticVars(ref vars) => {
                 try!(self.write_indented("static "));
@@ -136,7 +250,6 @@ impl<W: Write> PrettyPrinter<W> {
                     if i > 0 {
                         try!(self.write(", "));
                     }
-                    try!(self.write("$"));
                     try!(self.print_variable(varname));
                 }
                 self.write(";\n")
@@ -145,17 +258,38 @@ impl<W: Write> PrettyPrinter<W> {
     }
 
     fn print_member(&mut self, member: &Member) -> fmt::Result {
+        let attributes: &[AttributeGroup] = match *member {
+            Member::Constant(ref attributes, _, _, _) |
+            Member::EnumCase(ref attributes, _, _) |
+            Member::Property(ref attributes, _, _, _, _) => attributes,
+            Member::Method(_, _, ref decl) => &decl.attributes,
+            Member::TraitUse(_, _) => &[],
+        };
+        try!(self.print_attribute_lines(attributes));
         try!(self.write_indented(""));
         match *member {
-            Member::Constant(ref modifiers, ref name, ref value) => {
-                try!(write!(self.target, "{} const ", modifiers));
+            Member::EnumCase(_, ref name, ref value) => {
+                try!(self.write("case "));
+                try!(self.write(name.borrow()));
+                if let Some(ref value) = *value {
+                    try!(self.write(" = "));
+                    try!(self.print_expression(value));
+                }
+                self.write(";")
+            }
+            Member::Constant(_, ref modifiers, ref name, ref value) => {
+                try!(write!(self.target, "{}const ", modifiers));
                 try!(self.write(name.borrow()));
                 try!(self.write("="));
                 try!(self.print_expression(value));
                 self.write(";")
             }
-            Member::Property(ref modifiers, ref name, ref value) => {
-                try!(write!(self.target, "{} $", modifiers));
+            Member::Property(_, ref modifiers, ref ty, ref name, ref value) => {
+                try!(write!(self.target, "{}", modifiers));
+                if let Some(ref ty) = *ty {
+                    try!(write!(self.target, "{} ", ty));
+                }
+                try!(self.write("$"));
                 try!(self.write(name.borrow()));
                 if let Some(ref default) = *value {
                     try!(self.write("="));
@@ -164,7 +298,7 @@ impl<W: Write> PrettyPrinter<W> {
                 self.write(";")
             }
             Member::Method(ref modifiers, ref name, ref decl) => {
-                try!(write!(self.target, "{} ", modifiers));
+                try!(write!(self.target, "{}", modifiers));
                 self.print_function(decl, Some(name.borrow()))
             }
             Member::TraitUse(ref names, ref uses) => {
@@ -186,9 +320,13 @@ impl<W: Write> PrettyPrinter<W> {
                                     try!(write!(self.target, "{}::", path));
                                 }
                                 try!(self.write(method.borrow()));
-                                try!(self.write(" as "));
-                                try!(write!(self.target, "{} ", modifiers));
+                                try!(self.write(" as"));
+                                let modifiers = modifiers.to_string();
+                                if !modifiers.is_empty() {
+                                    try!(write!(self.target, " {}", modifiers.trim_end()));
+                                }
                                 if let Some(ref alias) = *alias {
+                                    try!(self.write(" "));
                                     try!(self.write(alias.borrow()));
                                 }
                             }
@@ -216,29 +354,44 @@ impl<W: Write> PrettyPrinter<W> {
         }
     }
 
-    fn print_block(&mut self, block: &Block) -> fmt::Result {
-        try!(self.write("{\n"));
+    fn print_inner_statements(&mut self, block: &Block) -> fmt::Result {
         self.indentation += 1;
         for stmt in &block.0 {
             try!(self.print_statement(stmt));
         }
         self.indentation -= 1;
+        Ok(())
+    }
+
+    fn print_block(&mut self, block: &Block) -> fmt::Result {
+        try!(self.write("{\n"));
+        try!(self.print_inner_statements(block));
         self.write_indented("}\n")
     }
 
-    fn print_function(&mut self, func: &FunctionDecl, name: Option<&str>) -> fmt::Result {
-        try!(self.write("function "));
-        if func.ret_ref {
-            try!(self.write("&"));
-        }
-        if let Some(name) = name {
-            try!(self.write(name));
+    /// print the body of a control structure in the syntax it was written in (e.g. `: ... endwhile;`)
+    fn print_control_block(&mut self, block: &Block, syntax: BlockSyntax, end_keyword: &str) -> fmt::Result {
+        match syntax {
+            BlockSyntax::Normal => {
+                try!(self.write(" "));
+                self.print_block(block)
+            }
+            BlockSyntax::Alternative => {
+                try!(self.write(":\n"));
+                try!(self.print_inner_statements(block));
+                try!(self.write_indented(end_keyword));
+                self.write(";\n")
+            }
         }
+    }
+
+    fn print_params(&mut self, params: &[ParamDefinition]) -> fmt::Result {
         try!(self.write("("));
-        for (i, param) in func.params.iter().enumerate() {
+        for (i, param) in params.iter().enumerate() {
             if i > 0 {
                 try!(self.write(","));
             }
+            try!(self.print_attributes_inline(&param.attributes));
             if let Some(ref ty) = param.ty {
                 try!(write!(self.target, "{} ", ty));
             }
@@ -255,9 +408,23 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.print_expression(default));
             }
         }
-        try!(self.write(") "));
+        self.write(")")
+    }
+
+    fn print_function(&mut self, func: &FunctionDecl, name: Option<&str>) -> fmt::Result {
+        if func.is_static {
+            try!(self.write("static "));
+        }
+        try!(self.write("function "));
+        if func.ret_ref {
+            try!(self.write("&"));
+        }
+        if let Some(name) = name {
+            try!(self.write(name));
+        }
+        try!(self.print_params(&func.params));
         if !func.usev.is_empty() {
-            try!(self.write("use ("));
+            try!(self.write(" use ("));
             for (i, &(ref by_ref, ref var)) in func.usev.iter().enumerate() {
                 if i > 0 {
                     try!(self.write(", "));
@@ -268,8 +435,12 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.write("$"));
                 try!(self.write(var.borrow()));
             }
-            try!(self.write(") "));
+            try!(self.write(")"));
+        }
+        if let Some(ref ret_ty) = func.ret_ty {
+            try!(write!(self.target, ": {}", ret_ty));
         }
+        try!(self.write(" "));
         if let Some(ref body) = func.body {
             self.print_block(body)
         } else {
@@ -280,18 +451,38 @@ impl<W: Write> PrettyPrinter<W> {
     fn print_use(&mut self, clauses: &[UseClause]) -> fmt::Result {
         for clause in clauses {
             try!(self.write_indented("use "));
-            match *clause {
-                UseClause::QualifiedName(ref path, ref alias) => {
-                    try!(write!(self.target, "{}", path));
-                    if let Some(ref alias) = *alias {
-                        try!(self.write(" as "));
-                        try!(self.write(alias.borrow()));
+            try!(self.print_use_clause(clause));
+            try!(self.write(";\n"));
+        }
+        Ok(())
+    }
+
+    fn print_use_clause(&mut self, clause: &UseClause) -> fmt::Result {
+        match *clause {
+            UseClause::QualifiedName(kind, ref path, ref alias) => {
+                try!(self.write(match kind {
+                    UseKind::Class => "",
+                    UseKind::Function => "function ",
+                    UseKind::Const => "const ",
+                }));
+                try!(write!(self.target, "{}", path));
+                if let Some(ref alias) = *alias {
+                    try!(self.write(" as "));
+                    try!(self.write(alias.borrow()));
+                }
+                Ok(())
+            }
+            UseClause::Group(ref prefix, ref clauses) => {
+                try!(write!(self.target, "{}\\{{", prefix));
+                for (i, clause) in clauses.iter().enumerate() {
+                    if i > 0 {
+                        try!(self.write(", "));
                     }
+                    try!(self.print_use_clause(clause));
                 }
+                self.write("}")
             }
-            try!(self.write(";\n"));
         }
-        Ok(())
     }
 
     fn print_statement(&mut self, stmt: &Stmt) -> fmt::Result {
@@ -340,7 +531,7 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.print_argument_list(args));
                 self.write(");\n")
             }
-            Stmt_::If(ref cond, ref bl, ref else_bl) => {
+            Stmt_::If(ref cond, ref bl, ref else_bl, BlockSyntax::Normal) => {
                 try!(self.write_indented("if ("));
                 try!(self.print_expression(cond));
                 try!(self.write(") "));
@@ -351,11 +542,37 @@ impl<W: Write> PrettyPrinter<W> {
                 }
                 Ok(())
             }
-            Stmt_::While(ref cond, ref bl) => {
+            Stmt_::If(ref cond, ref bl, ref else_bl, BlockSyntax::Alternative) => {
+                try!(self.write_indented("if ("));
+                try!(self.print_expression(cond));
+                try!(self.write("):\n"));
+                try!(self.print_inner_statements(bl));
+                let mut else_bl = else_bl;
+                loop {
+                    // an else block only containing an alternative if originates from an elseif
+                    match else_bl.0.first() {
+                        Some(&Stmt(Stmt_::If(ref cond, ref bl, ref next_else_bl, BlockSyntax::Alternative), _)) if else_bl.0.len() == 1 => {
+                            try!(self.write_indented("elseif ("));
+                            try!(self.print_expression(cond));
+                            try!(self.write("):\n"));
+                            try!(self.print_inner_statements(bl));
+                            else_bl = next_else_bl;
+                        }
+                        Some(_) => {
+                            try!(self.writeln("else:"));
+                            try!(self.print_inner_statements(else_bl));
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+                self.writeln("endif;")
+            }
+            Stmt_::While(ref cond, ref bl, syntax) => {
                 try!(self.write_indented("while ("));
                 try!(self.print_expression(cond));
-                try!(self.write(") "));
-                self.print_block(bl)
+                try!(self.write(")"));
+                self.print_control_block(bl, syntax, "endwhile")
             }
             Stmt_::DoWhile(ref bl, ref cond) => {
                 try!(self.write_indented("do "));
@@ -364,17 +581,17 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.print_expression(cond));
                 self.write(");\n")
             }
-            Stmt_::For(ref init, ref looper, ref cond, ref bl) => {
+            Stmt_::For(ref init, ref looper, ref cond, ref bl, syntax) => {
                 try!(self.write_indented("for ("));
                 try!(self.print_opt_expression(&init.as_ref().map(|x| &**x)));
                 try!(self.write("; "));
                 try!(self.print_opt_expression(&looper.as_ref().map(|x| &**x)));
                 try!(self.write(";"));
                 try!(self.print_opt_expression(&cond.as_ref().map(|x| &**x)));
-                try!(self.write(") "));
-                self.print_block(bl)
+                try!(self.write(")"));
+                self.print_control_block(bl, syntax, "endfor")
             }
-            Stmt_::ForEach(ref base, ref k, ref v, ref bl) => {
+            Stmt_::ForEach(ref base, ref k, ref v, ref bl, syntax) => {
                 try!(self.write_indented("foreach ("));
                 try!(self.print_expression(base));
                 try!(self.write(" as "));
@@ -383,15 +600,21 @@ impl<W: Write> PrettyPrinter<W> {
                     try!(self.write(" => "));
                 }
                 try!(self.print_expression(v));
-                try!(self.write(") "));
-                self.print_block(bl)
+                try!(self.write(")"));
+                self.print_control_block(bl, syntax, "endforeach")
             }
             Stmt_::Try(ref bl, ref catch, ref finally) => {
                 try!(self.write_indented("try "));
                 try!(self.print_block(bl));
                 for clause in catch {
                     try!(self.write("catch ("));
-                    try!(write!(self.target, "{} ${}) ", clause.ty, clause.var.borrow() as &str));
+                    for (i, ty) in clause.types.iter().enumerate() {
+                        if i > 0 {
+                            try!(self.write(" | "));
+                        }
+                        try!(write!(self.target, "{}", ty));
+                    }
+                    try!(write!(self.target, " ${}) ", clause.var.borrow() as &str));
                     try!(self.print_block(&clause.block));
                 }
                 if let Some(ref finally_bl) = *finally {
@@ -400,15 +623,46 @@ impl<W: Write> PrettyPrinter<W> {
                 }
                 Ok(())
             }
+            Stmt_::Declare(ref directives, ref body, syntax) => {
+                try!(self.write_indented("declare("));
+                for (i, &(ref name, ref value)) in directives.iter().enumerate() {
+                    if i > 0 {
+                        try!(self.write(", "));
+                    }
+                    try!(write!(self.target, "{}=", name.borrow() as &str));
+                    try!(self.print_expression(value));
+                }
+                try!(self.write(")"));
+                match *body {
+                    None => self.write(";\n"),
+                    Some(ref body) => self.print_control_block(body, syntax, "enddeclare"),
+                }
+            }
+            Stmt_::HaltCompiler(ref data) => {
+                try!(self.write_indented("__halt_compiler();"));
+                self.write(&String::from_utf8_lossy(data))
+            }
+            Stmt_::Goto(ref label) => {
+                try!(self.write_indented("goto "));
+                try!(self.write(label.borrow()));
+                self.write(";\n")
+            }
+            Stmt_::Label(ref label) => {
+                try!(self.write_indented(label.borrow()));
+                self.write(":\n")
+            }
             Stmt_::Throw(ref expr) => {
                 try!(self.write_indented("throw "));
                 try!(self.print_expression(expr));
                 self.write(";\n")
             }
-            Stmt_::Switch(ref base, ref cases) => {
+            Stmt_::Switch(ref base, ref cases, syntax) => {
                 try!(self.write_indented("switch ("));
                 try!(self.print_expression(base));
-                try!(self.write(") {\n"));
+                try!(self.write(match syntax {
+                    BlockSyntax::Normal => ") {\n",
+                    BlockSyntax::Alternative => "):\n",
+                }));
                 self.indentation += 1;
                 for case in cases {
                     for cond in &case.conds {
@@ -417,16 +671,15 @@ impl<W: Write> PrettyPrinter<W> {
                         try!(self.write(":\n"));
                     }
                     if case.default {
-                        try!(self.write("default:\n"));
-                    }
-                    self.indentation += 1;
-                    for stmt in &case.block.0 {
-                        try!(self.print_statement(stmt));
+                        try!(self.writeln("default:"));
                     }
-                    self.indentation -= 1;
+                    try!(self.print_inner_statements(&case.block));
                 }
                 self.indentation -= 1;
-                self.write("}\n")
+                self.writeln(match syntax {
+                    BlockSyntax::Normal => "}",
+                    BlockSyntax::Alternative => "endswitch;",
+                })
             }
         }
     }
@@ -450,10 +703,15 @@ impl<W: Write> PrettyPrinter<W> {
             Expr_::UnaryOp(_, _) |
             Expr_::ArrayIdx(_, _) |
             Expr_::ObjMember(_, _) |
+            Expr_::NullsafeObjMember(_, _) |
+            Expr_::NullsafeMethodCall(_, _, _) |
             Expr_::StaticMember(_, _) |
             Expr_::Call(_, _) |
             Expr_::New(_, _) |
+            Expr_::AnonymousClass(_, _, _, _, _) |
             Expr_::Assign(_, _) |
+            Expr_::Print(_) |
+            Expr_::ArrowFunction(_) |
             Expr_::TernaryIf(_, _, _) => true,
             _ => false,
         };
@@ -477,7 +735,13 @@ impl<W: Write> PrettyPrinter<W> {
             Variable::Name(ref name) => write!(self.target, "${}", name.borrow() as &str),
             Variable::Fetch(ref expr) => {
                 try!(self.write("$"));
-                self.print_expression_curly_parens(expr, true)
+                // only a variable can be fetched without braces (e.g. $$a)
+                if let Expr_::Variable(_) = expr.0 {
+                    return self.print_expression(expr);
+                }
+                try!(self.write("{"));
+                try!(self.print_expression(expr));
+                self.write("}")
             }
         }
     }
@@ -493,7 +757,11 @@ impl<W: Write> PrettyPrinter<W> {
                 }
                 self.write("'")
             }
-            Expr_::BinaryString(ref str_) => unimplemented!(),
+            Expr_::BinaryString(ref bytes) => {
+                try!(self.write("\""));
+                try!(self.print_escaped_bytes(bytes, '"'));
+                self.write("\"")
+            }
             Expr_::Int(ref i) => write!(self.target, "{}", i),
             Expr_::Double(ref d) => write!(self.target, "{}", d),
             Expr_::Array(ref arr) => {
@@ -535,6 +803,15 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.write("clone "));
                 self.print_expression(arg)
             }
+            Expr_::Print(ref arg) => {
+                try!(self.write("print "));
+                self.print_expression(arg)
+            }
+            Expr_::Eval(ref arg) => {
+                try!(self.write("eval("));
+                try!(self.print_expression(arg));
+                self.write(")")
+            }
             Expr_::Include(ref ty, ref arg) => {
                 try!(self.write(match *ty {
                     IncludeTy::Require => "require",
@@ -562,6 +839,42 @@ impl<W: Write> PrettyPrinter<W> {
                 }
                 Ok(())
             }
+            Expr_::Match(ref subject, ref arms) => {
+                try!(self.write("match ("));
+                try!(self.print_expression(subject));
+                try!(self.write(") {\n"));
+                self.indentation += 1;
+                for arm in arms {
+                    try!(self.write_indented(""));
+                    if arm.default {
+                        try!(self.write("default"));
+                    }
+                    for (i, cond) in arm.conds.iter().enumerate() {
+                        if i > 0 {
+                            try!(self.write(", "));
+                        }
+                        try!(self.print_expression(cond));
+                    }
+                    try!(self.write(" => "));
+                    try!(self.print_expression(&arm.body));
+                    try!(self.write(",\n"));
+                }
+                self.indentation -= 1;
+                self.write_indented("}")
+            }
+            Expr_::NullsafeObjMember(ref base, ref prop) => {
+                try!(self.print_expression_parens(base));
+                try!(self.write("?->"));
+                self.print_expression_curly_parens(prop, true)
+            }
+            Expr_::NullsafeMethodCall(ref base, ref method, ref args) => {
+                try!(self.print_expression_parens(base));
+                try!(self.write("?->"));
+                try!(self.print_expression_curly_parens(method, true));
+                try!(self.write("("));
+                try!(self.print_call_arguments(args));
+                self.write(")")
+            }
             Expr_::StaticMember(ref base, ref idxs) => {
                 try!(self.print_expression_parens(base));
                 for idx in idxs {
@@ -573,19 +886,35 @@ impl<W: Write> PrettyPrinter<W> {
             Expr_::Call(ref target, ref args) => {
                 try!(self.print_expression(target));
                 try!(self.write("("));
-                try!(self.print_argument_list(args));
+                try!(self.print_call_arguments(args));
                 self.write(")")
             }
             Expr_::New(ref target, ref args) => {
                 try!(self.write("new "));
                 try!(self.print_expression(target));
                 try!(self.write("("));
-                try!(self.print_argument_list(args));
+                try!(self.print_call_arguments(args));
                 self.write(")")
             },
-            Expr_::Unpack(ref arg) => {
-                try!(self.write("..."));
-                self.print_expression(arg)
+            Expr_::AnonymousClass(ref attributes, ref args, ref base_class, ref implements, ref members) => {
+                try!(self.write("new "));
+                try!(self.print_attributes_inline(attributes));
+                try!(self.write("class("));
+                try!(self.print_call_arguments(args));
+                try!(self.write(")"));
+                if let Some(ref base_class) = *base_class {
+                    try!(write!(self.target, " extends {}", base_class));
+                }
+                if !implements.is_empty() {
+                    try!(self.write(" implements "));
+                    for (i, iface) in implements.iter().enumerate() {
+                        if i > 0 {
+                            try!(self.write(", "));
+                        }
+                        try!(write!(self.target, "{}", iface));
+                    }
+                }
+                self.print_member_body(members)
             },
             Expr_::UnaryOp(ref operator, ref operand) => {
                 let (op, can_have_parens) = match *operator {
@@ -623,26 +952,53 @@ impl<W: Write> PrettyPrinter<W> {
             }
             Expr_::Cast(ref ty, ref op) => {
                 try!(self.write("("));
-                try!(self.write(match *ty {
-                    Ty::Array => "array",
-                    Ty::Callable => "callable",
-                    Ty::Bool => "bool",
-                    Ty::Float => "float",
-                    Ty::Int => "int",
-                    Ty::Double => "double",
-                    Ty::String => "string",
-                    Ty::Object(None) => "object",
-                    _ => unimplemented!(),
-                }));
+                try!(write!(self.target, "{}", ty));
                 try!(self.write(")("));
                 try!(self.print_expression(op));
                 self.write(")")
             }
+            Expr_::ShellExec(ref parts) => {
+                try!(self.write("`"));
+                for part in parts {
+                    match part.0 {
+                        Expr_::String(ref str_) => {
+                            let str_: &str = str_.borrow();
+                            try!(self.write(&str_.replace('\\', "\\\\").replace('`', "\\`").replace('$', "\\$")));
+                        }
+                        Expr_::BinaryString(ref bytes) => try!(self.print_escaped_bytes(bytes, '`')),
+                        _ => {
+                            try!(self.write("{"));
+                            try!(self.print_expression(part));
+                            try!(self.write("}"));
+                        }
+                    }
+                }
+                self.write("`")
+            }
             Expr_::Yield(ref expr) => {
                 try!(self.write("yield "));
                 self.print_opt_expression(&expr.as_ref().map(|x| &**x))
             }
-            Expr_::Function(ref decl) => self.print_function(decl, None),
+            Expr_::Function(ref decl) => {
+                try!(self.print_attributes_inline(&decl.attributes));
+                self.print_function(decl, None)
+            }
+            Expr_::ArrowFunction(ref decl) => {
+                try!(self.print_attributes_inline(&decl.attributes));
+                if decl.is_static {
+                    try!(self.write("static "));
+                }
+                try!(self.write("fn"));
+                if decl.ret_ref {
+                    try!(self.write("&"));
+                }
+                try!(self.print_params(&decl.params));
+                if let Some(ref ret_ty) = decl.ret_ty {
+                    try!(write!(self.target, ": {}", ret_ty));
+                }
+                try!(self.write(" => "));
+                self.print_expression(&decl.body)
+            }
             Expr_::Assign(ref target, ref value) => {
                 try!(self.print_expression(target));
                 try!(self.write("="));
@@ -658,19 +1014,21 @@ impl<W: Write> PrettyPrinter<W> {
                 try!(self.write("=&"));
                 self.print_expression(value)
             }
-            Expr_::List(ref parts) => {
-                try!(self.write("list("));
-                for (i, &(ref key, ref value)) in parts.iter().enumerate() {
+            Expr_::List(ref parts, short) => {
+                try!(self.write(if short { "[" } else { "list(" }));
+                for (i, part) in parts.iter().enumerate() {
                     if i > 0 {
                         try!(self.write(", "));
                     }
-                    if let Some(ref key) = *key {
-                        try!(self.print_expression(key));
-                        try!(self.write(" => "));
+                    if let Some((ref key, ref value)) = *part {
+                        if let Some(ref key) = *key {
+                            try!(self.print_expression(key));
+                            try!(self.write(" => "));
+                        }
+                        try!(self.print_expression(value));
                     }
-                    try!(self.print_expression(value));
                 }
-                self.write(")")
+                self.write(if short { "]" } else { ")" })
             }
             Expr_::TernaryIf(ref base, ref case_true, ref case_else) => {
                 try!(self.print_expression_parens(base));
@@ -711,6 +1069,7 @@ impl fmt::Display for Op {
             Op::Spaceship => "<=>",
             Op::Sl => "<<",
             Op::Sr => ">>",
+            Op::Coalesce => "??",
         })
     }
 }
@@ -729,7 +1088,9 @@ impl fmt::Display for ClassModifiers {
 
 impl fmt::Display for MemberModifiers {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.has(MemberModifier::Public) {
+        if self.has(MemberModifier::Var) {
+            try!(write!(f, "var "));
+        } else if self.has(MemberModifier::Public) {
             try!(write!(f, "public "));
         }
         if self.has(MemberModifier::Protected) {
@@ -753,8 +1114,10 @@ impl fmt::Display for MemberModifiers {
 
 impl fmt::Display for Path {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.is_absolute {
-            try!(write!(f, "\\"));
+        match self.kind {
+            PathKind::FullyQualified => try!(write!(f, "\\")),
+            PathKind::Relative => try!(write!(f, "namespace\\")),
+            PathKind::Unqualified | PathKind::Qualified => (),
         }
         if let Some(ref namespace) = self.namespace {
             try!(write!(f, "{}\\", namespace.borrow() as &str));
@@ -774,16 +1137,55 @@ impl fmt::Display for Ty {
             Ty::Int => "int",
             Ty::Double => "double",
             Ty::String => "string",
+            Ty::Void => "void",
+            Ty::Iterable => "iterable",
             Ty::Object(None) => "object",
             Ty::Object(Some(ref path)) => {
                 try!(write!(f, "{}", path));
                 return Ok(());
             }
+            Ty::Union(ref types) => {
+                for (i, ty) in types.iter().enumerate() {
+                    if i > 0 {
+                        try!(write!(f, "|"));
+                    }
+                    try!(write!(f, "{}", ty));
+                }
+                return Ok(());
+            }
         };
         write!(f, "{}", ty)
     }
 }
 
+impl fmt::Display for CastTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CastTy::Int => "int",
+            CastTy::Integer => "integer",
+            CastTy::Double => "double",
+            CastTy::Float => "float",
+            CastTy::Real => "real",
+            CastTy::String => "string",
+            CastTy::Binary => "binary",
+            CastTy::Array => "array",
+            CastTy::Object => "object",
+            CastTy::Bool => "bool",
+            CastTy::Boolean => "boolean",
+            CastTy::Unset => "unset",
+        })
+    }
+}
+
+impl fmt::Display for NullableTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.1 {
+            try!(write!(f, "?"));
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
 impl fmt::Display for Const {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", match *self {
@@ -1,3 +1,4 @@
+use std::rc::Rc;
 use parser::*;
 
 fn process_expr(input: &str) -> Expr {
@@ -74,9 +75,43 @@ fn parse_expr_string_fragmented() {
     assert_eq!(process_expr(r#""hello $wor->ld""#), enb!(0,16, Expr_::BinaryOp(Op::Concat, eb!(1,7, Expr_::String("hello ".into())), eb!(7,15, Expr_::ObjMember(
         eb!(7,11, Expr_::Variable("wor".into())), vec![ enb!(13,15, Expr_::Path(Path::identifier(false, "ld".into()))) ]
     )))));
+    assert_eq!(process_expr(r#""a $b[0]""#), enb!(0,9, Expr_::BinaryOp(Op::Concat, eb!(1,3, Expr_::String("a ".into())), eb!(3,8, Expr_::ArrayIdx(
+        eb!(3,5, Expr_::Variable("b".into())), vec![ Some(enb!(6,7, Expr_::Int(0))) ]
+    )))));
+    assert_eq!(process_expr(r#""a $b[key]""#), enb!(0,11, Expr_::BinaryOp(Op::Concat, eb!(1,3, Expr_::String("a ".into())), eb!(3,10, Expr_::ArrayIdx(
+        eb!(3,5, Expr_::Variable("b".into())), vec![ Some(enb!(6,9, Expr_::String("key".into()))) ]
+    )))));
+    assert_eq!(process_expr(r#""a $b[$c]""#), enb!(0,10, Expr_::BinaryOp(Op::Concat, eb!(1,3, Expr_::String("a ".into())), eb!(3,9, Expr_::ArrayIdx(
+        eb!(3,5, Expr_::Variable("b".into())), vec![ Some(enb!(6,8, Expr_::Variable("c".into()))) ]
+    )))));
+    assert_eq!(process_expr(r#""a {$b->c['d']->e()}""#), enb!(0,21, Expr_::BinaryOp(Op::Concat, eb!(1,3, Expr_::String("a ".into())), eb!(4,19, Expr_::Call(
+        eb!(4,17, Expr_::ObjMember(eb!(4,14, Expr_::ArrayIdx(eb!(4,9, Expr_::ObjMember(eb!(4,6, Expr_::Variable("b".into())), vec![
+            enb!(8,9, Expr_::Path(Path::identifier(false, "c".into())))
+        ])), vec![ Some(enb!(10,13, Expr_::String("d".into()))) ])), vec![
+            enb!(16,17, Expr_::Path(Path::identifier(false, "e".into())))
+        ])), vec![]
+    )))));
     assert_eq!(process_expr(r#""hello ${world}""#), enb!(0,16, Expr_::BinaryOp(Op::Concat, eb!(1,7, Expr_::String("hello ".into())), eb!(7,15, Expr_::Variable("world".into())))));
 }
 
+#[test]
+fn parse_expr_binary_string() {
+    // escape sequences which don't produce valid UTF-8
+    assert_eq!(process_expr(r#""\377""#), enb!(0,6, Expr_::BinaryString(Rc::new(vec![0xFF]))));
+    assert_eq!(process_expr(r#""\u{D800}""#), enb!(0,10, Expr_::BinaryString(Rc::new(vec![0xED, 0xA0, 0x80]))));
+    assert_eq!(process_expr(r#""$b\377""#), enb!(0,8, Expr_::BinaryOp(Op::Concat,
+        eb!(1,3, Expr_::Variable("b".into())), eb!(3,7, Expr_::BinaryString(Rc::new(vec![0xFF])))
+    )));
+    assert_eq!(process_expr(r#""$b\u{D800}""#), enb!(0,12, Expr_::BinaryOp(Op::Concat,
+        eb!(1,3, Expr_::Variable("b".into())), eb!(3,11, Expr_::BinaryString(Rc::new(vec![0xED, 0xA0, 0x80])))
+    )));
+    // the span of a fragment before a variable isn't reliable yet (see parse_expr_string)
+    match process_expr(r#""a\377$b""#).0 {
+        Expr_::BinaryOp(Op::Concat, ref left, _) => assert_eq!(left.0, Expr_::BinaryString(Rc::new(vec![b'a', 0xFF]))),
+        ref expr => panic!("unexpected {:?}", expr),
+    }
+}
+
 #[test]
 fn parse_expr_char_string() {
     assert_eq!(process_expr(r#"'\ntest\142'"#), enb!(0, 12, Expr_::String("\\ntest\\142".into())));
@@ -90,6 +125,18 @@ fn parse_ns_identifier() {
     assert_eq!(process_expr("Test\\Abc"), enb!(0, 8, Expr_::Path(Path::ns_identifier(false, "Test".into(), "Abc".into()))));
     assert_eq!(process_expr("Test\\Ns1\\Ns2"), enb!(0, 12, Expr_::Path(Path::ns_identifier(false, "Test\\Ns1".into(), "Ns2".into()))));
     assert_eq!(process_expr("\\Test\\Ns1\\Ns2\\Ns3"), enb!(0, 17, Expr_::Path(Path::ns_identifier(true, "Test\\Ns1\\Ns2".into(), "Ns3".into()))));
+    assert_eq!(process_expr("Test\\Abc").0, Expr_::Path(Path { kind: PathKind::Qualified, namespace: Some("Test".into()), identifier: "Abc".into() }));
+}
+
+#[test]
+fn parse_relative_name() {
+    assert_eq!(process_expr("namespace\\Test"), enb!(0, 14, Expr_::Path(Path { kind: PathKind::Relative, namespace: None, identifier: "Test".into() })));
+    assert_eq!(process_expr("namespace\\a\\b()"), enb!(0, 15, Expr_::Call(
+        eb!(0, 13, Expr_::Path(Path { kind: PathKind::Relative, namespace: Some("a".into()), identifier: "b".into() })), vec![]
+    )));
+    assert_eq!(process_expr("new namespace\\Bar"), enb!(0, 17, Expr_::New(
+        eb!(4, 17, Expr_::Path(Path { kind: PathKind::Relative, namespace: None, identifier: "Bar".into() })), vec![]
+    )));
 }
 
 #[test]
@@ -122,6 +169,51 @@ fn parse_expr_object_property() {
     )), vec![])));
 }
 
+#[test]
+fn parse_expr_nullsafe() {
+    assert_eq!(process_expr("$a?->b"), enb!(0,6, Expr_::NullsafeObjMember(eb!(0,2, Expr_::Variable("a".into())),
+        eb!(5,6, Expr_::Path(Path::identifier(false, "b".into()))))));
+    assert_eq!(process_expr("$a?->b(1)->c"), enb!(0,12, Expr_::ObjMember(
+        eb!(0,9, Expr_::NullsafeMethodCall(eb!(0,2, Expr_::Variable("a".into())), eb!(5,6, Expr_::Path(Path::identifier(false, "b".into()))),
+            vec![ arg!(enb!(7,8, Expr_::Int(1))) ])),
+        vec![ enb!(11,12, Expr_::Path(Path::identifier(false, "c".into()))) ]
+    )));
+    assert_eq!(process_expr(r#""x $a?->b""#), enb!(0,10, Expr_::BinaryOp(Op::Concat, eb!(1,3, Expr_::String("x ".into())),
+        eb!(3,9, Expr_::NullsafeObjMember(eb!(3,5, Expr_::Variable("a".into())), eb!(8,9, Expr_::Path(Path::identifier(false, "b".into()))))))));
+    for code in &["<?php $a?->b = 1;", "<?php $a?->b->c[] = 1;", "<?php $a?->b++;", "<?php [$a?->b] = $c;", "<?php unset($a?->b);",
+                  "<?php unset($x, $a?->b[0]);", "<?php foreach ($x as $a?->b) {}"] {
+        let err = Parser::parse_str(code).unwrap_err();
+        assert_eq!(err.error_message(None), "Can't use nullsafe operator in write context");
+    }
+    for code in &["<?php $x = &$a?->b;", "<?php $x = &$a?->b->c;", "<?php $x = [&$a?->b];", "<?php $x = array(1, &$a?->b());",
+                  "<?php [&$a?->b] = $c;", "<?php list('k' => &$a?->b) = $c;", "<?php foreach ($x as &$a?->b) {}"] {
+        let err = Parser::parse_str(code).unwrap_err();
+        assert_eq!(err.error_message(None), "Cannot take reference of a nullsafe chain");
+    }
+}
+
+#[test]
+fn parse_expr_closure_attributes() {
+    let attributes = vec![ AttributeGroup(vec![ Attribute { name: Path::identifier(false, "A".into()), args: vec![] } ]) ];
+    assert_eq!(process_expr("#[A] fn() => 1"), enb!(0,14, Expr_::ArrowFunction(ArrowFunctionDecl {
+        params: vec![], body: eb!(13,14, Expr_::Int(1)), ret_ref: false, is_static: false, attributes: attributes.clone(), ret_ty: None, captures: vec![],
+    })));
+    assert_eq!(process_expr("#[A] static function() {}"), enb!(0,25, Expr_::Function(FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: true, attributes: attributes, ret_ty: None,
+    })));
+}
+
+#[test]
+fn parse_expr_match() {
+    assert_eq!(process_expr("match ($a) { 1, 2, => 'x', default => 'y', }"), enb!(0,44, Expr_::Match(eb!(7,9, Expr_::Variable("a".into())), vec![
+        MatchArm { conds: vec![ enb!(13,14, Expr_::Int(1)), enb!(16,17, Expr_::Int(2)) ], default: false, body: enb!(22,25, Expr_::String("x".into())) },
+        MatchArm { conds: vec![], default: true, body: enb!(38,41, Expr_::String("y".into())) },
+    ])));
+    assert_eq!(process_expr("match (true) {}"), enb!(0,15, Expr_::Match(eb!(7,11, constant!(true)), vec![])));
+    let err = Parser::parse_str("<?php $r = match ($a) { default => 1, 2 => 3, default => 4 };").unwrap_err();
+    assert_eq!(err.error_message(None), "Match expressions may only contain one default arm");
+}
+
 #[test]
 fn parse_expr_array_idx() {
     assert_eq!(process_expr(r#"$test["a"]"#), enb!(0,10, Expr_::ArrayIdx(eb!(0,5, Expr_::Variable("test".into())), vec![ Some(enb!(6,9, Expr_::String("a".into()))) ])));
@@ -141,11 +233,11 @@ fn parse_expr_func_call() {
     assert_eq!(process_expr(r#"test()"#), enb!(0,6, Expr_::Call(eb!(0,4, Expr_::Path(Path::identifier(false, "test".into()))), vec![])));
     assert_eq!(process_expr(r#"array_()"#), enb!(0,8, Expr_::Call(eb!(0,6, Expr_::Path(Path::identifier(false, "array_".into()))), vec![])));
     assert_eq!(process_expr(r#"func_x(1, 2)"#), enb!(0,12, Expr_::Call(eb!(0,6, Expr_::Path(Path::identifier(false, "func_x".into()))),
-        vec![ enb!(7,8, Expr_::Int(1)), enb!(10,11, Expr_::Int(2)) ]
+        vec![ arg!(enb!(7,8, Expr_::Int(1))), arg!(enb!(10,11, Expr_::Int(2))) ]
     )));
     assert_eq!(process_expr(r#"func_x(abc(1), 2)"#), enb!(0,17, Expr_::Call(eb!(0,6, Expr_::Path(Path::identifier(false, "func_x".into()))), vec![
-        enb!(7,13, Expr_::Call(eb!(7,10, Expr_::Path(Path::identifier(false, "abc".into()))), vec![ enb!(11,12, Expr_::Int(1)) ])),
-        enb!(15, 16, Expr_::Int(2))
+        arg!(enb!(7,13, Expr_::Call(eb!(7,10, Expr_::Path(Path::identifier(false, "abc".into()))), vec![ arg!(enb!(11,12, Expr_::Int(1))) ]))),
+        arg!(enb!(15, 16, Expr_::Int(2)))
     ])));
     assert_eq!(process_expr(r#"$g[0]()"#), enb!(0,7, Expr_::Call(eb!(0,5, Expr_::ArrayIdx(eb!(0,2, Expr_::Variable("g".into())), vec![
         Some(enb!(3,4, Expr_::Int(0))) ])), vec![])));
@@ -155,14 +247,14 @@ fn parse_expr_func_call() {
                 eb!(0,5, Expr_::ArrayIdx(eb!(0,2, Expr_::Variable("g".into())), vec![ Some(enb!(3,4, Expr_::Int(0))) ])),
                 vec![]
             )), vec![ Some(enb!(8,9, Expr_::Int(1))) ]
-        )), vec![ enb!(11,15, constant!(true)) ]
+        )), vec![ arg!(enb!(11,15, constant!(true))) ]
     )));
 }
 
 #[test]
 fn parse_expr_require() {
     assert_eq!(process_expr("abc(require $path)"), enb!(0,18, Expr_::Call(eb!(0,3, Expr_::Path(Path::identifier(false, "abc".into()))),
-        vec![ enb!(4,17, Expr_::Include(IncludeTy::Require, eb!(12,17, Expr_::Variable("path".into())))) ])
+        vec![ arg!(enb!(4,17, Expr_::Include(IncludeTy::Require, eb!(12,17, Expr_::Variable("path".into()))))) ])
     ));
 }
 
@@ -176,16 +268,39 @@ fn parse_expr_empty() {
     assert_eq!(process_expr("empty($b)"), enb!(0,9, Expr_::Empty(eb!(6,8, Expr_::Variable("b".into())))));
 }
 
+#[test]
+fn parse_expr_shell_exec() {
+    assert_eq!(process_expr("``"), enb!(0,2, Expr_::ShellExec(vec![])));
+    assert_eq!(process_expr("`ls $dir -l`"), enb!(0,12, Expr_::ShellExec(vec![
+        enb!(1,4, Expr_::String("ls ".into())),
+        enb!(4,8, Expr_::Variable("dir".into())),
+        enb!(8,11, Expr_::String(" -l".into())),
+    ])));
+    assert_eq!(process_expr("`echo {$a->b}`"), enb!(0,14, Expr_::ShellExec(vec![
+        enb!(1,6, Expr_::String("echo ".into())),
+        enb!(7,12, Expr_::ObjMember(eb!(7,9, Expr_::Variable("a".into())), vec![
+            enb!(11,12, Expr_::Path(Path::identifier(false, "b".into())))
+        ])),
+    ])));
+}
+
 #[test]
 fn parse_expr_cast() {
-    assert_eq!(process_expr("(bool) $test"), enb!(0, 12, Expr_::Cast(Ty::Bool, eb!(7,12, Expr_::Variable("test".into())))));
-    assert_eq!(process_expr("(int)$a['a']"), enb!(0, 12, Expr_::Cast(Ty::Int, eb!(5,12, Expr_::ArrayIdx(eb!(5,7, Expr_::Variable("a".into())), vec![
+    assert_eq!(process_expr("(bool) $test"), enb!(0, 12, Expr_::Cast(CastTy::Bool, eb!(7,12, Expr_::Variable("test".into())))));
+    assert_eq!(process_expr("(int)$a['a']"), enb!(0, 12, Expr_::Cast(CastTy::Int, eb!(5,12, Expr_::ArrayIdx(eb!(5,7, Expr_::Variable("a".into())), vec![
         Some(enb!(8,11, Expr_::String("a".into())))
     ])))));
-    assert_eq!(process_expr("(int)ab()['c']"), enb!(0,14, Expr_::Cast(Ty::Int, eb!(5,14, Expr_::ArrayIdx(
+    assert_eq!(process_expr("(int)ab()['c']"), enb!(0,14, Expr_::Cast(CastTy::Int, eb!(5,14, Expr_::ArrayIdx(
         eb!(5,9, Expr_::Call(eb!(5,7, Expr_::Path(Path::identifier(false, "ab".into()))), vec![])),
         vec![ Some(enb!(10,13, Expr_::String("c".into()))) ]
     )))));
+    assert_eq!(process_expr("(integer)$a"), enb!(0,11, Expr_::Cast(CastTy::Integer, eb!(9,11, Expr_::Variable("a".into())))));
+    assert_eq!(process_expr("(real)$a"), enb!(0,8, Expr_::Cast(CastTy::Real, eb!(6,8, Expr_::Variable("a".into())))));
+    assert_eq!(process_expr("(unset)$a"), enb!(0,9, Expr_::Cast(CastTy::Unset, eb!(7,9, Expr_::Variable("a".into())))));
+    // casts are case-insensitive and may contain whitespace
+    assert_eq!(process_expr("(INT)$x"), enb!(0,7, Expr_::Cast(CastTy::Int, eb!(5,7, Expr_::Variable("x".into())))));
+    assert_eq!(process_expr("(Integer)$x"), enb!(0,11, Expr_::Cast(CastTy::Integer, eb!(9,11, Expr_::Variable("x".into())))));
+    assert_eq!(process_expr("( int )$x"), enb!(0,9, Expr_::Cast(CastTy::Int, eb!(7,9, Expr_::Variable("x".into())))));
 }
 
 #[test]
@@ -220,6 +335,18 @@ fn parse_expr_static_property() {
     assert_eq!(process_expr("static::$abc"), enb!(0,12, Expr_::StaticMember(eb!(0,6, Expr_::Path(Path::identifier(false, "static".into()))), vec![ enb!(8,12, Expr_::Variable("abc".into())) ])));
 }
 
+#[test]
+fn parse_expr_coalesce() {
+    assert_eq!(process_expr("$a??$b??$c"), enb!(0,10, Expr_::BinaryOp(Op::Coalesce, eb!(0,2, Expr_::Variable("a".into())),
+        eb!(4,10, Expr_::BinaryOp(Op::Coalesce, eb!(4,6, Expr_::Variable("b".into())), eb!(8,10, Expr_::Variable("c".into()))))
+    )));
+    assert_eq!(process_expr("$a||$b??$c"), enb!(0,10, Expr_::BinaryOp(Op::Coalesce,
+        eb!(0,6, Expr_::BinaryOp(Op::Or, eb!(0,2, Expr_::Variable("a".into())), eb!(4,6, Expr_::Variable("b".into())))),
+        eb!(8,10, Expr_::Variable("c".into()))
+    )));
+    assert_eq!(process_expr("$a??=1"), enb!(0,6, Expr_::CompoundAssign(eb!(0,2, Expr_::Variable("a".into())), Op::Coalesce, eb!(5,6, Expr_::Int(1)))));
+}
+
 #[test]
 fn parse_expr_ternary() {
     assert_eq!(process_expr("$test?true:false"), enb!(0,16, Expr_::TernaryIf(eb!(0,5, Expr_::Variable("test".into())),
@@ -245,6 +372,38 @@ fn parse_expr_new() {
     assert_eq!(process_expr("new Foo"), enb!(0,7, Expr_::New(eb!(4,7, Expr_::Path(Path::identifier(false, "Foo".into()))), vec![])));
 }
 
+#[test]
+fn parse_expr_new_anonymous_class() {
+    assert_eq!(process_expr("new class {}"), enb!(0,12, Expr_::AnonymousClass(vec![], vec![], None, vec![], vec![])));
+    assert_eq!(process_expr("new class($a) extends A implements B, C { public $x; }"), enb!(0,54, Expr_::AnonymousClass(
+        vec![],
+        vec![ arg!(enb!(10,12, Expr_::Variable("a".into()))) ],
+        Some(Path::identifier(false, "A".into())),
+        vec![ Path::identifier(false, "B".into()), Path::identifier(false, "C".into()) ],
+        vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public]), None, "x".into(), None) ],
+    )));
+    assert_eq!(process_expr("new #[A] class {}"), enb!(0,17, Expr_::AnonymousClass(
+        vec![ AttributeGroup(vec![ Attribute { name: Path::identifier(false, "A".into()), args: vec![] } ]) ], vec![], None, vec![], vec![]
+    )));
+    assert!(Parser::parse_str("<?php new #[A] Foo;").is_err());
+}
+
+#[test]
+fn parse_expr_print_eval() {
+    assert_eq!(process_expr("print $a"), enb!(0,8, Expr_::Print(eb!(6,8, Expr_::Variable("a".into())))));
+    assert_eq!(process_expr("print $a and $b"), enb!(0,15, Expr_::BinaryOp(Op::And,
+        eb!(0,8, Expr_::Print(eb!(6,8, Expr_::Variable("a".into())))), eb!(13,15, Expr_::Variable("b".into()))
+    )));
+    assert_eq!(process_expr("eval($a)"), enb!(0,8, Expr_::Eval(eb!(5,7, Expr_::Variable("a".into())))));
+}
+
+#[test]
+fn parse_expr_variable_variable() {
+    assert_eq!(process_expr("$$a"), enb!(0,3, Expr_::Variable(Variable::Fetch(eb!(1,3, Expr_::Variable("a".into()))))));
+    assert_eq!(process_expr("$$$a"), enb!(0,4, Expr_::Variable(Variable::Fetch(eb!(1,4, Expr_::Variable(Variable::Fetch(eb!(2,4, Expr_::Variable("a".into())))))))));
+    assert_eq!(process_expr("${'a'}"), enb!(0,6, Expr_::Variable(Variable::Fetch(eb!(2,5, Expr_::String("a".into()))))));
+}
+
 #[test]
 fn parse_expr_clone() {
     assert_eq!(process_expr("clone $test"), enb!(0,5, Expr_::Clone(eb!(6,11, Expr_::Variable("test".into())))));
@@ -283,6 +442,16 @@ fn parse_expr_array() {
     assert_eq!(process_expr("array()"), enb!(0,7, Expr_::Array(vec![])));
 }
 
+#[test]
+fn parse_expr_nested_array_arguments() {
+    // every bracket is only parsed once, so deep nesting doesn't take exponential time
+    let depth = 16;
+    let code = format!("<?php $x = {}1{};", "f([".repeat(depth), "])".repeat(depth));
+    assert!(Parser::parse_str(&code).is_ok());
+    let code = format!("<?php {}$a{} = $b;", "[".repeat(depth), "]".repeat(depth));
+    assert!(Parser::parse_str(&code).is_ok());
+}
+
 #[test]
 fn parse_expr_assoc_array() {
     assert_eq!(process_expr("['a' => 'b']"), enb!(0,12, Expr_::Array(vec![
@@ -294,7 +463,54 @@ fn parse_expr_assoc_array() {
 fn parse_expr_closure() {
     assert_eq!(process_expr("function () { c(); }"), enb!(0,20, Expr_::Function(FunctionDecl {
         params: vec![],
-        body: Some(Block(vec![ senb!(14,17, Expr_::Call(eb!(14,15, Expr_::Path(Path::identifier(false, "c".into()))), vec![])) ])), usev: vec![], ret_ref: false,
+        body: Some(Block(vec![ senb!(14,17, Expr_::Call(eb!(14,15, Expr_::Path(Path::identifier(false, "c".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None,
+    })));
+}
+
+#[test]
+fn parse_expr_static_closure() {
+    assert_eq!(process_expr("static function &() use (&$a) {}"), enb!(0,32, Expr_::Function(FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![(true, "a".into())], ret_ref: true, is_static: true, attributes: vec![], ret_ty: None,
+    })));
+    assert_eq!(process_expr("$b = static function () {}"), enb!(0,26, Expr_::Assign(eb!(0,2, Expr_::Variable("b".into())), eb!(5,26, Expr_::Function(FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: true, attributes: vec![], ret_ty: None,
+    })))));
+}
+
+#[test]
+fn parse_expr_arrow_function() {
+    assert_eq!(process_expr("fn($x) => $x * $y"), enb!(0,17, Expr_::ArrowFunction(ArrowFunctionDecl {
+        params: vec![ ParamDefinition { attributes: vec![], name: "x".into(), as_ref: false, variadic: false, ty: None, default: None } ],
+        body: eb!(10,17, Expr_::BinaryOp(Op::Mul, eb!(10,12, Expr_::Variable("x".into())), eb!(15,17, Expr_::Variable("y".into())))),
+        ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, captures: vec!["y".into()],
+    })));
+    assert_eq!(process_expr("static fn&(): int => $this->a"), enb!(0,29, Expr_::ArrowFunction(ArrowFunctionDecl {
+        params: vec![],
+        body: eb!(21,29, Expr_::ObjMember(eb!(21,26, Expr_::Variable("this".into())), vec![
+            enb!(28,29, Expr_::Path(Path::identifier(false, "a".into())))
+        ])),
+        ret_ref: true, is_static: true, attributes: vec![], ret_ty: Some(NullableTy(Ty::Int, false)), captures: vec![],
+    })));
+}
+
+#[test]
+fn parse_expr_arrow_function_nested_captures() {
+    let captures = |expr: Expr| match expr.0 {
+        Expr_::ArrowFunction(decl) => decl.captures,
+        _ => unreachable!(),
+    };
+    assert_eq!(captures(process_expr("fn($x) => fn($y) => $x + $y + $z")), vec!["z".into()]);
+    assert_eq!(captures(process_expr("fn() => function () use ($a, &$b) { return $c; }")), vec!["a".into(), "b".into()]);
+    // static property names aren't variables, but dynamic ones contain variables
+    assert_eq!(captures(process_expr("fn() => A::$s")), vec![]);
+    assert_eq!(captures(process_expr("fn() => $c::$s + A::$$x + A::${$e}")), vec!["c".into(), "x".into(), "e".into()]);
+}
+
+#[test]
+fn parse_expr_closure_return_type() {
+    assert_eq!(process_expr("function () use ($a): iterable {}"), enb!(0,33, Expr_::Function(FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![(false, "a".into())], ret_ref: false, is_static: false, attributes: vec![],
+        ret_ty: Some(NullableTy(Ty::Iterable, false)),
     })));
 }
 
@@ -311,6 +527,22 @@ fn parse_expr_priority_parents_call() {
 #[test]
 fn parse_expr_unpack() {
     assert_eq!(process_expr("t(...$email)"), enb!(0,12, Expr_::Call(eb!(0,1, Expr_::Path(Path::identifier(false, "t".into()))), vec![
-        enb!(2,11, Expr_::Unpack(eb!(5,11, Expr_::Variable("email".into()))))
+        Argument { name: None, value: enb!(5,11, Expr_::Variable("email".into())), by_ref: false, unpack: true, span: mk_span(8usize, 17usize) }
+    ])));
+}
+
+#[test]
+fn parse_expr_named_arguments() {
+    assert_eq!(process_expr("foo(timeout: 5, retry: true)"), enb!(0,28, Expr_::Call(eb!(0,3, Expr_::Path(Path::identifier(false, "foo".into()))), vec![
+        Argument { name: Some("timeout".into()), value: enb!(13,14, Expr_::Int(5)), by_ref: false, unpack: false, span: mk_span(10usize, 20usize) },
+        Argument { name: Some("retry".into()), value: enb!(23,27, constant!(true)), by_ref: false, unpack: false, span: mk_span(22usize, 33usize) },
+    ])));
+    assert_eq!(process_expr("new A($b, array: 1)"), enb!(0,19, Expr_::New(eb!(4,5, Expr_::Path(Path::identifier(false, "A".into()))), vec![
+        arg!(enb!(6,8, Expr_::Variable("b".into()))),
+        Argument { name: Some("array".into()), value: enb!(17,18, Expr_::Int(1)), by_ref: false, unpack: false, span: mk_span(16usize, 24usize) },
     ])));
+    let err = Parser::parse_str("<?php f(a: 1, 2);").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use positional argument after named argument");
+    let err = Parser::parse_str("<?php $o->m(a: 1, ...$b);").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use argument unpacking after named arguments");
 }
@@ -1,4 +1,6 @@
+use std::rc::Rc;
 use parser::*;
+use printer::PrettyPrinter;
 
 fn process_script(input: &str) -> Vec<Stmt> {
     Parser::parse_str(&input).unwrap()
@@ -15,4 +17,91 @@ fn parse_simple_file_echos() {
     ]);
 }
 
+#[test]
+fn parse_halt_compiler() {
+    assert_eq!(process_script("<?php echo 1; __halt_compiler();\x00data<?php"), vec![
+        rsnb!(6,13, Stmt_::Echo(vec![ rnb!(11,12, Expr_::Int(1)) ])),
+        rsnb!(14,32, Stmt_::HaltCompiler(Rc::new(b"\x00data<?php".to_vec()))),
+    ]);
+    assert_eq!(process_script("<?php __halt_compiler() ?>data"), vec![
+        rsnb!(6,23, Stmt_::HaltCompiler(Rc::new(b"data".to_vec()))),
+    ]);
+    let err = Parser::parse_str("<?php function f() { __halt_compiler(); }").unwrap_err();
+    assert_eq!(err.error_message(None), "__HALT_COMPILER() can only be used from the outermost scope");
+}
+
+#[test]
+fn parse_goto_label() {
+    assert_eq!(process_script("<?php goto end; echo 1; end:"), vec![
+        rsnb!(6,15, Stmt_::Goto("end".into())),
+        rsnb!(16,23, Stmt_::Echo(vec![ rnb!(21,22, Expr_::Int(1)) ])),
+        rsnb!(24,28, Stmt_::Label("end".into())),
+    ]);
+    // jumping out of a loop is allowed
+    assert!(Parser::parse_str("<?php while (true) { goto a; } a: echo 1;").is_ok());
+}
+
+#[test]
+fn parse_goto_into_loop() {
+    let err = Parser::parse_str("<?php goto a; while (true) { a: echo 1; }").unwrap_err();
+    assert_eq!(err.error_message(None), "'goto' into loop or switch statement is disallowed");
+    let err = Parser::parse_str("<?php function f() { goto a; switch (1) { case 1: a: break; } }").unwrap_err();
+    assert_eq!(err.error_message(None), "'goto' into loop or switch statement is disallowed");
+}
+
+#[test]
+fn parse_multiple_namespaces() {
+    assert_eq!(process_script("<?php namespace A; echo 1; namespace B; echo 2;"), vec![
+        rsnb!(6,26, Stmt_::Decl(Decl::Namespace(Some(Path::identifier(false, "A".into())), Block(vec![
+            rsnb!(19,26, Stmt_::Echo(vec![ rnb!(24,25, Expr_::Int(1)) ])),
+        ]), false))),
+        rsnb!(27,47, Stmt_::Decl(Decl::Namespace(Some(Path::identifier(false, "B".into())), Block(vec![
+            rsnb!(40,47, Stmt_::Echo(vec![ rnb!(45,46, Expr_::Int(2)) ])),
+        ]), false))),
+    ]);
+    assert_eq!(process_script("<?php namespace A { echo 1; } namespace { echo 2; }"), vec![
+        rsnb!(6,29, Stmt_::Decl(Decl::Namespace(Some(Path::identifier(false, "A".into())), Block(vec![
+            rsnb!(20,27, Stmt_::Echo(vec![ rnb!(25,26, Expr_::Int(1)) ])),
+        ]), true))),
+        rsnb!(30,51, Stmt_::Decl(Decl::Namespace(None, Block(vec![
+            rsnb!(42,49, Stmt_::Echo(vec![ rnb!(47,48, Expr_::Int(2)) ])),
+        ]), true))),
+    ]);
+}
+
+#[test]
+fn parse_invalid_namespaces() {
+    let err = Parser::parse_str("<?php namespace A; namespace B {}").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot mix bracketed namespace declarations with unbracketed namespace declarations");
+    let err = Parser::parse_str("<?php namespace A { namespace B; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Namespace declarations cannot be nested");
+    let err = Parser::parse_str("<?php namespace A {} echo 1;").unwrap_err();
+    assert_eq!(err.error_message(None), "No code may exist outside of namespace {}");
+}
+
 // TEST invalid cases TODO: like <?php echo "test" (missing semicolon, should actually parse?)
+
+#[test]
+fn parse_invalid_unicode_escape() {
+    let err = Parser::parse_str(r#"<?php echo "\u{zz}";"#).unwrap_err();
+    assert_eq!(err.error_message(None), "Invalid UTF-8 codepoint escape sequence");
+}
+
+#[test]
+fn print_attributed_declarations() {
+    let code = "<?php #[A] class A {} #[T] trait T {} #[I] interface I {} #[E] enum E { #[C] case C; } $x = 1;";
+    let mut printed = String::new();
+    PrettyPrinter::print_statements(&mut printed, process_script(code)).unwrap();
+    assert_eq!(printed, "#[A]\nclass A {\n}\n#[T]\ntrait T {\n}\n#[I]\ninterface I {\n}\n#[E]\nenum E {\n    #[C]\n    case C;\n}\n$x=1;\n");
+    // the printed code parses (and prints) the same again
+    let mut reprinted = String::new();
+    PrettyPrinter::print_statements(&mut reprinted, process_script(&format!("<?php {}", printed))).unwrap();
+    assert_eq!(reprinted, printed);
+}
+
+#[test]
+fn print_var_property() {
+    let mut printed = String::new();
+    PrettyPrinter::print_statements(&mut printed, process_script("<?php class A { var int $u; public $v; }")).unwrap();
+    assert_eq!(printed, "class A {\n    var int $u;\n    public $v;\n}\n");
+}
@@ -29,6 +29,14 @@ macro_rules! rsnb {
     ($s:expr, $end:expr, $st:expr) => {Stmt($st, Span { start: $s, end: $end, ..Span::new()})};
 }
 
+/// positional call argument, spanned like its value
+macro_rules! arg {
+    ($e:expr) => {{
+        let value = $e;
+        Argument { name: None, span: value.1.clone(), value: value, by_ref: false, unpack: false }
+    }};
+}
+
 macro_rules! constant {
     (true) => {Expr_::Constant(Const::True)};
     (false) => {Expr_::Constant(Const::False)};
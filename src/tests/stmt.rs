@@ -50,7 +50,7 @@ fn parse_stmt_new() {
 #[test]
 fn parse_stmt_throw() {
     assert_eq!(process_stmt(r#"throw new Exception("test");"#), st!(0,28, Stmt_::Throw(eb!(6,27, Expr_::New(eb!(10,19, Expr_::Path(Path::identifier(false, "Exception".into()))),
-        vec![ enb!(20,26, Expr_::String("test".into())) ])))
+        vec![ arg!(enb!(20,26, Expr_::String("test".into()))) ])))
     ));
     assert_eq!(process_stmt(r#"throw new Exception;"#), st!(0,20, Stmt_::Throw(eb!(6,19, Expr_::New(eb!(10,19, Expr_::Path(Path::identifier(false, "Exception".into()))), vec![])))));
 }
@@ -100,17 +100,17 @@ fn parse_stmt_if_assign() {
     assert_eq!(process_stmt("if (! $a && $b = $c) { echo 1; }"), st!(0,32, Stmt_::If(eb!(4,19, Expr_::BinaryOp(Op::And,
         eb!(4,8, Expr_::UnaryOp(UnaryOp::Not, eb!(6,8, Expr_::Variable("a".into())))),
         eb!(12,19, Expr_::Assign(eb!(12,14, Expr_::Variable("b".into())), eb!(17,19, Expr_::Variable("c".into())))))
-    ), Block(vec![ st!(23,30, Stmt_::Echo(vec![ enb!(28,29, Expr_::Int(1)) ])) ]), Block(vec![]))));
+    ), Block(vec![ st!(23,30, Stmt_::Echo(vec![ enb!(28,29, Expr_::Int(1)) ])) ]), Block(vec![]), BlockSyntax::Normal)));
 }
 
 #[test]
 fn parse_stmt_if_while() {
     assert_eq!(process_stmt("if   ($a) { b(); }"), st!(0,18, Stmt_::If(eb!(6,8, Expr_::Variable("a".into())), Block(vec![
         senb!(12,15, Expr_::Call(eb!(12,13, Expr_::Path(Path::identifier(false, "b".into()))), vec![]))
-    ]), Block(vec![]))));
+    ]), Block(vec![]), BlockSyntax::Normal)));
     assert_eq!(process_stmt("while($a) b();"), st!(0,14, Stmt_::While(eb!(6,8, Expr_::Variable("a".into())), Block(vec![
         senb!(10,13, Expr_::Call(eb!(10,11, Expr_::Path(Path::identifier(false, "b".into()))), vec![]))
-    ]))));
+    ]), BlockSyntax::Normal)));
 }
 
 #[test]
@@ -120,11 +120,13 @@ fn parse_stmt_if_else() {
         eb!(4,6, Expr_::Variable("a".into())),
         Block(vec![ senb!(10,13, Expr_::Call(eb!(10,11, Expr_::Path(Path::identifier(false, "a".into()))), vec![])) ]),
         Block(vec![ senb!(24,27, Expr_::Call(eb!(24,25, Expr_::Path(Path::identifier(false, "b".into()))), vec![])) ]),
+        BlockSyntax::Normal,
     )));
     assert_eq!(process_stmt("if ($a) a(); else b();"), st!(0,22, Stmt_::If(
         eb!(4,6, Expr_::Variable("a".into())),
         Block(vec![ senb!(8,11, Expr_::Call(eb!(8,9, Expr_::Path(Path::identifier(false, "a".into()))), vec![])) ]),
         Block(vec![ senb!(18,21, Expr_::Call(eb!(18,19, Expr_::Path(Path::identifier(false, "b".into()))), vec![])) ]),
+        BlockSyntax::Normal,
     )));
     //if, elseif, else
     assert_eq!(process_stmt("if ($a) a(); else if ($b) b(); else c();"), st!(0,40, Stmt_::If(
@@ -134,7 +136,9 @@ fn parse_stmt_if_else() {
             eb!(22,24, Expr_::Variable("b".into())),
             Block(vec![ senb!(26,29, Expr_::Call(eb!(26,27, Expr_::Path(Path::identifier(false, "b".into()))), vec![])) ]),
             Block(vec![ senb!(36,39, Expr_::Call(eb!(36,37, Expr_::Path(Path::identifier(false, "c".into()))), vec![])) ]),
+            BlockSyntax::Normal,
         )) ]),
+        BlockSyntax::Normal,
     )));
     assert_eq!(process_stmt("if ($a) a(); elseif ($b)  b(); else c();"), st!(0,40, Stmt_::If(
         eb!(4,6, Expr_::Variable("a".into())),
@@ -143,15 +147,39 @@ fn parse_stmt_if_else() {
             eb!(21,23, Expr_::Variable("b".into())),
             Block(vec![ senb!(26,29, Expr_::Call(eb!(26,27, Expr_::Path(Path::identifier(false, "b".into()))), vec![])) ]),
             Block(vec![ senb!(36,39, Expr_::Call(eb!(36,37, Expr_::Path(Path::identifier(false, "c".into()))), vec![])) ]),
+            BlockSyntax::Normal,
+        )) ]),
+        BlockSyntax::Normal,
+    )));
+}
+
+#[test]
+fn parse_stmt_alternative_syntax() {
+    assert_eq!(process_stmt("if ($a): a(); elseif ($b): b(); else: c(); endif;"), st!(0,49, Stmt_::If(
+        eb!(4,6, Expr_::Variable("a".into())),
+        Block(vec![ senb!(9,12, Expr_::Call(eb!(9,10, Expr_::Path(Path::identifier(false, "a".into()))), vec![])) ]),
+        Block(vec![ st!(14,42, Stmt_::If(
+            eb!(22,24, Expr_::Variable("b".into())),
+            Block(vec![ senb!(27,30, Expr_::Call(eb!(27,28, Expr_::Path(Path::identifier(false, "b".into()))), vec![])) ]),
+            Block(vec![ senb!(38,41, Expr_::Call(eb!(38,39, Expr_::Path(Path::identifier(false, "c".into()))), vec![])) ]),
+            BlockSyntax::Alternative,
         )) ]),
+        BlockSyntax::Alternative,
     )));
+    assert_eq!(process_stmt("while ($a): b(); endwhile;"), st!(0,26, Stmt_::While(eb!(7,9, Expr_::Variable("a".into())), Block(vec![
+        senb!(12,15, Expr_::Call(eb!(12,13, Expr_::Path(Path::identifier(false, "b".into()))), vec![]))
+    ]), BlockSyntax::Alternative)));
+    assert_eq!(process_stmt("switch ($a): endswitch;"), st!(0,23, Stmt_::Switch(eb!(8,10, Expr_::Variable("a".into())), vec![], BlockSyntax::Alternative)));
+    assert_eq!(process_stmt("foreach ($a as $v): endforeach;"), st!(0,31, Stmt_::ForEach(eb!(9,11, Expr_::Variable("a".into())), None,
+        eb!(15,17, Expr_::Variable("v".into())), Block(vec![]), BlockSyntax::Alternative)));
+    assert_eq!(process_stmt("for (;;): endfor;"), st!(0,17, Stmt_::For(None, None, None, Block(vec![]), BlockSyntax::Alternative)));
 }
 
 #[test]
 fn parse_stmt_do_while() {
     assert_eq!(process_stmt("do { test(); } while(count($a));"), st!(0,32, Stmt_::DoWhile(
         Block(vec![ senb!(5,11, Expr_::Call(eb!(5,9, Expr_::Path(Path::identifier(false, "test".into()))), vec![])) ]),
-        eb!(21,30, Expr_::Call(eb!(21,26, Expr_::Path(Path::identifier(false, "count".into()))), vec![ enb!(27,29, Expr_::Variable("a".into())) ]))
+        eb!(21,30, Expr_::Call(eb!(21,26, Expr_::Path(Path::identifier(false, "count".into()))), vec![ arg!(enb!(27,29, Expr_::Variable("a".into()))) ]))
     )));
 }
 
@@ -162,6 +190,7 @@ fn parse_stmt_for() {
         Some(eb!(13,20, Expr_::BinaryOp(Op::Lt, eb!(13,15, Expr_::Variable("i".into())), eb!(18,20, Expr_::Int(10))))),
         Some(eb!(22,26, Expr_::UnaryOp(UnaryOp::PostInc, eb!(22,24, Expr_::Variable("i".into()))))),
         Block(vec![ st!(30,37, Stmt_::Echo(vec![ enb!(35,36, Expr_::Int(1)) ])) ]),
+        BlockSyntax::Normal,
     )));
 }
 
@@ -171,13 +200,25 @@ fn parse_stmt_foreach() {
         eb!(9,14, Expr_::Variable("test".into())),
         None, // key
         eb!(18,20, Expr_::Variable("v".into())), // value
-        Block(vec![ senb!(24,28, Expr_::Call(eb!(24,26, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ]) //body
+        Block(vec![ senb!(24,28, Expr_::Call(eb!(24,26, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ]), //body
+        BlockSyntax::Normal,
     )));
     assert_eq!(process_stmt("foreach ($test as $k => $v) { ok(); }"), st!(0,37, Stmt_::ForEach(
         eb!(9,14, Expr_::Variable("test".into())),
         Some(eb!(18,20, Expr_::Variable("k".into()))), // key
         eb!(24,26, Expr_::Variable("v".into())), // value
-        Block(vec![ senb!(30,34, Expr_::Call(eb!(30,32, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ]) //body
+        Block(vec![ senb!(30,34, Expr_::Call(eb!(30,32, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ]), //body
+        BlockSyntax::Normal,
+    )));
+    assert_eq!(process_stmt("foreach ($test as [$a, list($b)]) {}"), st!(0,36, Stmt_::ForEach(
+        eb!(9,14, Expr_::Variable("test".into())),
+        None,
+        eb!(18,32, Expr_::List(vec![
+            Some((None, enb!(19,21, Expr_::Variable("a".into())))),
+            Some((None, enb!(23,31, Expr_::List(vec![ Some((None, enb!(28,30, Expr_::Variable("b".into())))) ], false)))),
+        ], true)),
+        Block(vec![]),
+        BlockSyntax::Normal,
     )));
 }
 
@@ -187,13 +228,14 @@ fn parse_stmt_instanceof() {
         eb!(4,31, Expr_::InstanceOf(eb!(4,11, Expr_::Variable("result".into())), eb!(23,31,Expr_::Path(Path::identifier(false, "Response".into()))))),
         Block(vec![ st!(35,50, Stmt_::Return(Some(eb!(42,49,Expr_::Variable("result".into()))))) ]),
         Block::empty(),
+        BlockSyntax::Normal,
     )));
 }
 
 #[test]
 fn parse_stmt_new_as_param() {
     assert_eq!(process_stmt("r(new Foo);"), senb!(0,10, Expr_::Call(eb!(0,1, Expr_::Path(Path::identifier(false, "r".into()))), vec![
-        enb!(2,9, Expr_::New(eb!(6,9, Expr_::Path(Path::identifier(false, "Foo".into()))), vec![]))
+        arg!(enb!(2,9, Expr_::New(eb!(6,9, Expr_::Path(Path::identifier(false, "Foo".into()))), vec![])))
     ])));
 }
 
@@ -201,7 +243,7 @@ fn parse_stmt_new_as_param() {
 fn parse_stmt_try() {
     assert_eq!(process_stmt(r#"try { echo "ok"; } catch (Exception $e) { return false;}"#), st!(0, 56, Stmt_::Try(
         Block(vec![ st!(6,16, Stmt_::Echo(vec![ enb!(11,15, Expr_::String("ok".into())) ])) ]),
-        vec![ CatchClause { ty: Path::identifier(false, "Exception".into()), var: "e".into(),
+        vec![ CatchClause { types: vec![Path::identifier(false, "Exception".into())], var: "e".into(),
             block: Block(vec![ st!(42,55, Stmt_::Return(Some(eb!(49,54, constant!(false))))) ]),
         } ],
         None,
@@ -209,21 +251,43 @@ fn parse_stmt_try() {
     assert_eq!(process_stmt(r#"try { echo "ok"; } catch (Exception $e) { return false; } catch (Throwable $e) { return true; }"#), st!(0,95, Stmt_::Try(
         Block(vec![ st!(6,16, Stmt_::Echo(vec![ enb!(11,15, Expr_::String("ok".into())) ])) ]),
         vec![
-            CatchClause { ty: Path::identifier(false, "Exception".into()), var: "e".into(), block: Block(vec![ st!(42,55, Stmt_::Return(Some(eb!(49,54, constant!(false))))) ]) },
-            CatchClause { ty: Path::identifier(false, "Throwable".into()), var: "e".into(), block: Block(vec![ st!(81,93, Stmt_::Return(Some(eb!(88,92, constant!(true))))) ]) },
+            CatchClause { types: vec![Path::identifier(false, "Exception".into())], var: "e".into(), block: Block(vec![ st!(42,55, Stmt_::Return(Some(eb!(49,54, constant!(false))))) ]) },
+            CatchClause { types: vec![Path::identifier(false, "Throwable".into())], var: "e".into(), block: Block(vec![ st!(81,93, Stmt_::Return(Some(eb!(88,92, constant!(true))))) ]) },
         ],
         None,
     )));
+    assert_eq!(process_stmt(r#"try {} catch (A | \B\C $e) {}"#), st!(0,29, Stmt_::Try(
+        Block(vec![]),
+        vec![ CatchClause {
+            types: vec![ Path::identifier(false, "A".into()), Path::ns_identifier(true, "B".into(), "C".into()) ],
+            var: "e".into(), block: Block(vec![]),
+        } ],
+        None,
+    )));
 }
 
 #[test]
 fn parse_stmt_use() {
-    assert_eq!(process_stmt("use Test;"), st!(0,9, Stmt_::Use(vec![ UseClause::QualifiedName(Path::identifier(false, "Test".into()), None) ])));
-    assert_eq!(process_stmt(r#"use Ab\Cd\Ef\Gh\Ij as Ga;"#), st!(0, 25, Stmt_::Use(vec![UseClause::QualifiedName(
+    assert_eq!(process_stmt("use Test;"), st!(0,9, Stmt_::Use(vec![ UseClause::QualifiedName(UseKind::Class, Path::identifier(false, "Test".into()), None) ])));
+    assert_eq!(process_stmt(r#"use Ab\Cd\Ef\Gh\Ij as Ga;"#), st!(0, 25, Stmt_::Use(vec![UseClause::QualifiedName(UseKind::Class, 
         Path::ns_identifier(false, "Ab\\Cd\\Ef\\Gh".into(), "Ij".into()),
         Some("Ga".into()))
     ])));
-    assert_eq!(process_stmt(r#"use \FQNS\Test;"#), st!(0,15, Stmt_::Use(vec![ UseClause::QualifiedName(Path::ns_identifier(true, "FQNS".into(), "Test".into()), None) ])));
+    assert_eq!(process_stmt("use function Foo\\bar, Foo\\baz;"), st!(0,30, Stmt_::Use(vec![
+        UseClause::QualifiedName(UseKind::Function, Path::ns_identifier(false, "Foo".into(), "bar".into()), None),
+        UseClause::QualifiedName(UseKind::Function, Path::ns_identifier(false, "Foo".into(), "baz".into()), None),
+    ])));
+    assert_eq!(process_stmt(r#"use Foo\{A, B\C as D, function e, const F,};"#), st!(0,44, Stmt_::Use(vec![ UseClause::Group(Path::identifier(false, "Foo".into()), vec![
+        UseClause::QualifiedName(UseKind::Class, Path::identifier(false, "A".into()), None),
+        UseClause::QualifiedName(UseKind::Class, Path::ns_identifier(false, "B".into(), "C".into()), Some("D".into())),
+        UseClause::QualifiedName(UseKind::Function, Path::identifier(false, "e".into()), None),
+        UseClause::QualifiedName(UseKind::Const, Path::identifier(false, "F".into()), None),
+    ]) ])));
+    assert_eq!(process_stmt(r#"use function \Foo\{a, b};"#), st!(0,25, Stmt_::Use(vec![ UseClause::Group(Path::identifier(true, "Foo".into()), vec![
+        UseClause::QualifiedName(UseKind::Function, Path::identifier(false, "a".into()), None),
+        UseClause::QualifiedName(UseKind::Function, Path::identifier(false, "b".into()), None),
+    ]) ])));
+    assert_eq!(process_stmt(r#"use \FQNS\Test;"#), st!(0,15, Stmt_::Use(vec![ UseClause::QualifiedName(UseKind::Class, Path::ns_identifier(true, "FQNS".into(), "Test".into()), None) ])));
 }
 
 #[test]
@@ -234,7 +298,8 @@ fn parse_stmt_switch() {
                 st!(25,34, Stmt_::Echo(vec![ enb!(30,33, Expr_::String("1".into())) ])), st!(35,41, Stmt_::Break(None))
             ])},
             SwitchCase { default: true, conds: vec![], block: Block(vec![ st!(51,60, Stmt_::Echo(vec![ enb!(56,59, Expr_::String("2".into())) ])) ]) },
-        ]
+        ],
+        BlockSyntax::Normal,
     )));
     assert_eq!(process_stmt(r#"switch ($test) { case 1: echo "1"; default: echo "2"; }"#), st!(0,55, Stmt_::Switch(eb!(8,13, Expr_::Variable("test".into())),
         vec![
@@ -242,140 +307,303 @@ fn parse_stmt_switch() {
             st!(25,34, Stmt_::Echo(vec![ enb!(30,33, Expr_::String("1".into())) ]))
         ]) },
         SwitchCase { default: true, conds: vec![], block: Block(vec![ st!(44,53, Stmt_::Echo(vec![ enb!(49,52, Expr_::String("2".into())) ])) ]) }
-    ])));
+    ], BlockSyntax::Normal)));
     assert_eq!(process_stmt("switch ($test) { case 1: case 2: echo 1; }"), st!(0,42, Stmt_::Switch(eb!(8,13, Expr_::Variable("test".into())), vec![
         SwitchCase { default: false, conds: vec![ enb!(22,23, Expr_::Int(1)), enb!(30,31, Expr_::Int(2)) ], block: Block(vec![
             st!(33,40, Stmt_::Echo(vec![ enb!(38,39, Expr_::Int(1)) ]))
         ])}
-    ])));
+    ], BlockSyntax::Normal)));
     assert_eq!(process_stmt("switch ($test) { case 1: case 2: case 3: case 4: echo 1; }"), st!(0,58, Stmt_::Switch(eb!(8,13, Expr_::Variable("test".into())), vec![
         SwitchCase { default: false,
             conds: vec![ enb!(22,23, Expr_::Int(1)), enb!(30,31, Expr_::Int(2)), enb!(38,39, Expr_::Int(3)), enb!(46,47, Expr_::Int(4)) ],
             block: Block(vec![ st!(49,56, Stmt_::Echo(vec![ enb!(54,55, Expr_::Int(1)) ]))])
         }
-    ])));
+    ], BlockSyntax::Normal)));
 }
 
 #[test]
 fn parse_stmt_func_decl() {
     assert_eq!(process_stmt("function test() { ok(); }"), st!(0,25, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl { params: vec![],
-        body: Some(Block(vec![ senb!(18,22, Expr_::Call(eb!(18,20, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, })
+        body: Some(Block(vec![ senb!(18,22, Expr_::Call(eb!(18,20, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
     )));
     assert_eq!(process_stmt("function &test() { ok(); }"), st!(0,26, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl { params: vec![],
-        body: Some(Block(vec![ senb!(19,23, Expr_::Call(eb!(19,21, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: true, })
+        body: Some(Block(vec![ senb!(19,23, Expr_::Call(eb!(19,21, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: true, is_static: false, attributes: vec![], ret_ty: None, })
     )));
     assert_eq!(process_stmt("function test($a) { ok(); }"), st!(0,27, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
-        params: vec![ParamDefinition { name: "a".into(), as_ref: false, variadic: false, ty: None, default: None }],
-        body: Some(Block(vec![ senb!(20,24, Expr_::Call(eb!(20,22, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, })
+        params: vec![ParamDefinition { attributes: vec![], name: "a".into(), as_ref: false, variadic: false, ty: None, default: None }],
+        body: Some(Block(vec![ senb!(20,24, Expr_::Call(eb!(20,22, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
     )));
     assert_eq!(process_stmt("function test($a, $b) { ok(); }"), st!(0,31, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
         params: vec![
-            ParamDefinition { name: "a".into(), as_ref: false, variadic: false, ty: None, default: None },
-            ParamDefinition { name: "b".into(), as_ref: false, variadic: false, ty: None, default: None }
+            ParamDefinition { attributes: vec![], name: "a".into(), as_ref: false, variadic: false, ty: None, default: None },
+            ParamDefinition { attributes: vec![], name: "b".into(), as_ref: false, variadic: false, ty: None, default: None }
         ],
-        body: Some(Block(vec![ senb!(24,28, Expr_::Call(eb!(24,26, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, })
+        body: Some(Block(vec![ senb!(24,28, Expr_::Call(eb!(24,26, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
     )));
     assert_eq!(process_stmt("function test(...$a) { ok(); }"), st!(0,30, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
-        params: vec![ParamDefinition { name: "a".into(), as_ref: false, variadic: true, ty: None, default: None }],
-        body: Some(Block(vec![ senb!(23,27, Expr_::Call(eb!(23,25, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, })
+        params: vec![ParamDefinition { attributes: vec![], name: "a".into(), as_ref: false, variadic: true, ty: None, default: None }],
+        body: Some(Block(vec![ senb!(23,27, Expr_::Call(eb!(23,25, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
     )));
 }
 
 #[test]
 fn parse_func_decl_typehint() {
     assert_eq!(process_stmt("function test(Test $a) { ok(); }"), st!(0,32, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
-        params: vec![ ParamDefinition { name: "a".into(), as_ref: false, variadic: false, ty: Some(Ty::Object(Some(Path::identifier(false, "Test".into())))), default: None } ],
-        body: Some(Block(vec![ senb!(25,29, Expr_::Call(eb!(25,27, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, })
+        params: vec![ ParamDefinition { attributes: vec![], name: "a".into(), as_ref: false, variadic: false, ty: Some(NullableTy(Ty::Object(Some(Path::identifier(false, "Test".into()))), false)), default: None } ],
+        body: Some(Block(vec![ senb!(25,29, Expr_::Call(eb!(25,27, Expr_::Path(Path::identifier(false, "ok".into()))), vec![])) ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
+    )));
+    assert_eq!(process_stmt("function test(int|string &$a) {}"), st!(0,32, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
+        params: vec![ ParamDefinition { attributes: vec![], name: "a".into(), as_ref: true, variadic: false, ty: Some(NullableTy(Ty::Union(vec![Ty::Int, Ty::String]), false)), default: None } ],
+        body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None, })
+    )));
+    assert!(Parser::parse_str("<?php function test(int| $a) {}").is_err());
+    assert!(Parser::parse_str("<?php function test($a $b) {}").is_err());
+}
+
+#[test]
+fn parse_func_decl_return_type() {
+    assert_eq!(process_stmt("function test(?int $a): ?string {}"), st!(0,34, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
+        params: vec![ ParamDefinition { attributes: vec![], name: "a".into(), as_ref: false, variadic: false, ty: Some(NullableTy(Ty::Int, true)), default: None } ],
+        body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: Some(NullableTy(Ty::String, true)), })
+    )));
+    assert_eq!(process_stmt("function test(): void {}"), st!(0,24, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: Some(NullableTy(Ty::Void, false)), })
+    )));
+    assert_eq!(process_stmt("function test(): self {}"), st!(0,24, Stmt_::Decl(Decl::GlobalFunction("test".into(), FunctionDecl {
+        params: vec![], body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![],
+        ret_ty: Some(NullableTy(Ty::Object(Some(Path::identifier(false, "self".into()))), false)), })
     )));
 }
 
 #[test]
 fn parse_class_decl() {
     assert_eq!(process_stmt("class Test {}"), st!(0,13, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![], members: vec![]
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![], members: vec![]
     }))));
     assert_eq!(process_stmt("final class Test {}"), st!(0,19, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::new(&[ClassModifier::Final]), name: "Test".into(), base_class: None, implements: vec![], members: vec![]
+        attributes: vec![], cmod: ClassModifiers::new(&[ClassModifier::Final]), name: "Test".into(), base_class: None, implements: vec![], members: vec![]
     }))));
     assert_eq!(process_stmt("class Test extends Abc\\Test2 {}"), st!(0,31, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: Some(Path::ns_identifier(false, "Abc".into(), "Test2".into())), implements: vec![], members: vec![]
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: Some(Path::ns_identifier(false, "Abc".into(), "Test2".into())), implements: vec![], members: vec![]
     }))));
     assert_eq!(process_stmt("class Test implements ITest {}"), st!(0,30, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![Path::identifier(false, "ITest".into())], members: vec![]
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![Path::identifier(false, "ITest".into())], members: vec![]
     }))));
 }
 
 #[test]
 fn parse_class_properties() {
     assert_eq!(process_stmt("class Test { public $test; }"), st!(0,28, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
-        members: vec![ Member::Property(MemberModifiers::new(&[MemberModifier::Public]), "test".into(), None)],
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public]), None, "test".into(), None)],
     }))));
     assert_eq!(process_stmt("class Test { protected $ab = []; }"), st!(0,34, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
-        members: vec![ Member::Property(MemberModifiers::new(&[MemberModifier::Protected]), "ab".into(), Some(enb!(29,31, Expr_::Array(vec![])))) ],
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Protected]), None, "ab".into(), Some(enb!(29,31, Expr_::Array(vec![])))) ],
     }))));
 }
 
+#[test]
+fn parse_class_typed_properties() {
+    assert_eq!(process_stmt("class Test { private ?int $count = 0; }"), st!(0,39, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Private]), Some(NullableTy(Ty::Int, true)), "count".into(), Some(enb!(35,36, Expr_::Int(0)))) ],
+    }))));
+    assert_eq!(process_stmt("class Test { public Foo|Bar $x; }"), st!(0,33, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public]), Some(NullableTy(Ty::Union(vec![
+            Ty::Object(Some(Path::identifier(false, "Foo".into()))), Ty::Object(Some(Path::identifier(false, "Bar".into()))),
+        ]), false)), "x".into(), None) ],
+    }))));
+    assert_eq!(process_stmt("class Test { var int $a, $b; }"), st!(0,30, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![
+            Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public, MemberModifier::Var]), Some(NullableTy(Ty::Int, false)), "a".into(), None),
+            Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public, MemberModifier::Var]), Some(NullableTy(Ty::Int, false)), "b".into(), None),
+        ],
+    }))));
+    assert_eq!(process_stmt("class Test { public static ?\\A\\B $c; }"), st!(0,38, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Property(vec![], MemberModifiers::new(&[MemberModifier::Public, MemberModifier::Static]),
+            Some(NullableTy(Ty::Object(Some(Path::ns_identifier(true, "A".into(), "B".into()))), true)), "c".into(), None) ],
+    }))));
+    let err = Parser::parse_str("<?php class Test { public callable $x; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Property cannot have type callable");
+    assert_eq!(err.span(), mk_span(26usize, 34usize));
+    let err = Parser::parse_str("<?php class Test { public int|callable $x; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Property cannot have type callable");
+}
+
 #[test]
 fn parse_class_const() {
     assert_eq!(process_stmt("class Test { const C=true; }"), st!(0, 28, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
-        members: vec![ Member::Constant(MemberModifiers::none(), "C".into(), enb!(21,25, constant!(true))) ]
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Constant(vec![], MemberModifiers::none(), "C".into(), enb!(21,25, constant!(true))) ]
+    }))));
+    assert_eq!(process_stmt("class Test { private const C=1; }"), st!(0, 33, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        members: vec![ Member::Constant(vec![], MemberModifiers::new(&[MemberModifier::Private]), "C".into(), enb!(29,30, Expr_::Int(1))) ]
     }))));
 }
 
+#[test]
+fn parse_class_const_invalid_modifiers() {
+    let err = Parser::parse_str("<?php class Test { static const C=1; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use 'static' as constant modifier");
+    assert_eq!(err.span(), mk_span(19usize, 25usize));
+    let err = Parser::parse_str("<?php class Test { public abstract const C=1; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use 'abstract' as constant modifier");
+    assert_eq!(err.span(), mk_span(26usize, 34usize));
+}
+
+#[test]
+fn parse_class_member_invalid_modifiers() {
+    let err = Parser::parse_str("<?php class Test { public private const X = 1; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple access type modifiers are not allowed");
+    assert_eq!(err.span(), mk_span(26usize, 33usize));
+    let err = Parser::parse_str("<?php class Test { public public $x; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple access type modifiers are not allowed");
+    let err = Parser::parse_str("<?php class Test { static public static function f() {} }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple static modifiers are not allowed");
+    assert_eq!(err.span(), mk_span(33usize, 39usize));
+    let err = Parser::parse_str("<?php abstract class Test { abstract abstract function f(); }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple abstract modifiers are not allowed");
+    let err = Parser::parse_str("<?php class Test { final final function f() {} }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple final modifiers are not allowed");
+}
+
 #[test]
 fn parse_class_methods() {
     assert_eq!(process_stmt("class Test { public function a() { run(); } }"), st!(0,45, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
         members: vec![ Member::Method(MemberModifiers::new(&[MemberModifier::Public]), "a".into(), FunctionDecl {
             params: vec![], body: Some(Block(vec![ senb!(35,40, Expr_::Call(eb!(35,38, Expr_::Path(Path::identifier(false, "run".into()))), vec![])) ])),
-            usev: vec![], ret_ref: false,
+            usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None,
         })]
     }))));
     assert_eq!(process_stmt("class Test { public function __construct(array $param1 = []) { $this->param = $param1; } }"),
         st!(0,90, Stmt_::Decl(Decl::Class(ClassDecl {
-            cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
+            attributes: vec![], cmod: ClassModifiers::none(), name: "Test".into(), base_class: None, implements: vec![],
             members: vec![ Member::Method(MemberModifiers::new(&[MemberModifier::Public]), "__construct".into(), FunctionDecl {
-                params: vec![ParamDefinition { name: "param1".into(), as_ref: false, variadic: false, ty: Some(Ty::Array), default: Some(enb!(57,59, Expr_::Array(vec![]))) }],
+                params: vec![ParamDefinition { attributes: vec![], name: "param1".into(), as_ref: false, variadic: false, ty: Some(NullableTy(Ty::Array, false)), default: Some(enb!(57,59, Expr_::Array(vec![]))) }],
                 body: Some(Block(vec![ senb!(63,85, Expr_::Assign(eb!(63,75, Expr_::ObjMember(eb!(63,68, Expr_::Variable("this".into())), vec![
                     enb!(70,75, Expr_::Path(Path::identifier(false, "param".into()))) ])), eb!(78,85, Expr_::Variable("param1".into()))))
-                ])), usev: vec![], ret_ref: false,
+                ])), usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None,
             })]
         })))
     );
 }
 
+#[test]
+fn parse_attributes() {
+    let attribute = |name: &str, args| Attribute { name: Path::identifier(false, name.into()), args: args };
+    assert_eq!(process_stmt("#[A(1)] function f(#[B] $x) {}"), st!(0,30, Stmt_::Decl(Decl::GlobalFunction("f".into(), FunctionDecl {
+        params: vec![ ParamDefinition { attributes: vec![ AttributeGroup(vec![ attribute("B", vec![]) ]) ], name: "x".into(), as_ref: false, variadic: false, ty: None, default: None } ],
+        body: Some(Block(vec![])), usev: vec![], ret_ref: false, is_static: false,
+        attributes: vec![ AttributeGroup(vec![ attribute("A", vec![ arg!(enb!(4,5, Expr_::Int(1))) ]) ]) ], ret_ty: None,
+    }))));
+    assert_eq!(process_stmt("#[A, B] class C { #[D] public $e; #[E] const F = 1; }"), st!(0,53, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![ AttributeGroup(vec![ attribute("A", vec![]), attribute("B", vec![]) ]) ], cmod: ClassModifiers::none(), name: "C".into(),
+        base_class: None, implements: vec![], members: vec![
+            Member::Property(vec![ AttributeGroup(vec![ attribute("D", vec![]) ]) ], MemberModifiers::new(&[MemberModifier::Public]), None, "e".into(), None),
+            Member::Constant(vec![ AttributeGroup(vec![ attribute("E", vec![]) ]) ], MemberModifiers::none(), "F".into(), enb!(49,50, Expr_::Int(1))),
+        ],
+    }))));
+    assert_eq!(process_stmt("#[A] interface I {}"), st!(0,19, Stmt_::Decl(Decl::Interface(
+        vec![ AttributeGroup(vec![ attribute("A", vec![]) ]) ], "I".into(), vec![], vec![]
+    ))));
+    assert_eq!(process_stmt("#[B] trait T {}"), st!(0,15, Stmt_::Decl(Decl::Trait(
+        vec![ AttributeGroup(vec![ attribute("B", vec![]) ]) ], "T".into(), vec![]
+    ))));
+    assert_eq!(process_stmt("#[A] enum Suit { #[B] case Hearts; }"), st!(0,36, Stmt_::Decl(Decl::Enum(EnumDecl {
+        attributes: vec![ AttributeGroup(vec![ attribute("A", vec![]) ]) ], name: "Suit".into(), backing_ty: None, implements: vec![],
+        members: vec![ Member::EnumCase(vec![ AttributeGroup(vec![ attribute("B", vec![]) ]) ], "Hearts".into(), None) ],
+    }))));
+    // attributes can't be placed on a trait use
+    assert!(Parser::parse_str("<?php class A { #[X] use T; }").is_err());
+}
+
 #[test]
 fn parse_class_trait_use() {
     assert_eq!(process_stmt("class Test { use Abc; }"), st!(0,23, Stmt_::Decl(Decl::Class(ClassDecl { name: "Test".into(), base_class: None, implements: vec![], members: vec![
         Member::TraitUse(vec![Path::identifier(false, "Abc".into())], vec![])
-    ], cmod: ClassModifiers::none() }))));
+    ], attributes: vec![], cmod: ClassModifiers::none() }))));
 }
 
 #[test]
 fn parse_stmt_list() {
     assert_eq!(process_stmt("list($a, $b) = test();"), senb!(0,21, Expr_::Assign(eb!(0,12, Expr_::List(
-        vec![ (None, enb!(5,7, Expr_::Variable("a".into()))), (None, enb!(9,11, Expr_::Variable("b".into()))) ]
+        vec![ Some((None, enb!(5,7, Expr_::Variable("a".into())))), Some((None, enb!(9,11, Expr_::Variable("b".into())))) ], false
     )), eb!(15,21, Expr_::Call(eb!(15,19, Expr_::Path(Path::identifier(false, "test".into()))), vec![])))));
+    assert_eq!(process_stmt("list(, $b) = $c;"), senb!(0,15, Expr_::Assign(eb!(0,10, Expr_::List(
+        vec![ None, Some((None, enb!(7,9, Expr_::Variable("b".into())))) ], false
+    )), eb!(13,15, Expr_::Variable("c".into())))));
+}
+
+#[test]
+fn parse_stmt_short_list() {
+    assert_eq!(process_stmt("[$a, &$b] = $c;"), senb!(0,14, Expr_::Assign(eb!(0,9, Expr_::List(vec![
+        Some((None, enb!(1,3, Expr_::Variable("a".into())))),
+        Some((None, enb!(5,8, Expr_::Reference(eb!(6,8, Expr_::Variable("b".into())))))),
+    ], true)), eb!(12,14, Expr_::Variable("c".into())))));
+    assert_eq!(process_stmt("['id' => $id, 'x' => [, $y]] = $c;"), senb!(0,33, Expr_::Assign(eb!(0,28, Expr_::List(vec![
+        Some((Some(enb!(1,5, Expr_::String("id".into()))), enb!(9,12, Expr_::Variable("id".into())))),
+        Some((Some(enb!(14,17, Expr_::String("x".into()))), enb!(21,27, Expr_::List(vec![
+            None, Some((None, enb!(24,26, Expr_::Variable("y".into())))),
+        ], true)))),
+    ], true)), eb!(31,33, Expr_::Variable("c".into())))));
+    // without assignment it's still an array
+    assert_eq!(process_stmt("[$a, &$b];"), senb!(0,9, Expr_::Array(vec![
+        (None, enb!(1,3, Expr_::Variable("a".into()))),
+        (None, enb!(5,8, Expr_::Reference(eb!(6,8, Expr_::Variable("b".into()))))),
+    ])));
+}
+
+#[test]
+fn parse_stmt_empty_list() {
+    let err = Parser::parse_str("<?php [] = $a;").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use empty list");
+    let err = Parser::parse_str("<?php list($a, list(,)) = $a;").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use empty list");
+    let err = Parser::parse_str("<?php $a = [1, , 2];").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use empty array elements in arrays");
+    // an invalid element is a syntax error and not a skipped element
+    let err = Parser::parse_str("<?php $a = [1, +];").unwrap_err();
+    assert!(err.error_message(None) != "Cannot use empty array elements in arrays");
+}
+
+#[test]
+fn parse_enum_decl() {
+    assert_eq!(process_stmt("enum Suit: string implements I { case Hearts = 'H'; const X = 1; }"), st!(0,66, Stmt_::Decl(Decl::Enum(EnumDecl {
+        attributes: vec![], name: "Suit".into(), backing_ty: Some(Ty::String), implements: vec![ Path::identifier(false, "I".into()) ],
+        members: vec![
+            Member::EnumCase(vec![], "Hearts".into(), Some(enb!(47,50, Expr_::String("H".into())))),
+            Member::Constant(vec![], MemberModifiers::none(), "X".into(), enb!(62,63, Expr_::Int(1))),
+        ],
+    }))));
+    // enum isn't a reserved word
+    assert_eq!(process_stmt("enum();"), senb!(0,6, Expr_::Call(eb!(0,4, Expr_::Path(Path::identifier(false, "enum".into()))), vec![])));
+    let err = Parser::parse_str("<?php enum E: float {}").unwrap_err();
+    assert_eq!(err.error_message(None), "Enum backing type must be int or string");
+    let err = Parser::parse_str("<?php class C { case X; }").unwrap_err();
+    assert_eq!(err.error_message(None), "Case can only be used in enums");
 }
 
 #[test]
 fn parse_trait_decl() {
-    assert_eq!(process_stmt("trait Test {}"), st!(0,13, Stmt_::Decl(Decl::Trait("Test".into(), vec![]))));
+    assert_eq!(process_stmt("trait Test {}"), st!(0,13, Stmt_::Decl(Decl::Trait(vec![], "Test".into(), vec![]))));
     // http://php.net/manual/de/language.oop5.traits.php
-    assert_eq!(process_stmt("trait HelloWorld {use Hello, World;}"), st!(0,36, Stmt_::Decl(Decl::Trait("HelloWorld".into(), vec![
+    assert_eq!(process_stmt("trait HelloWorld {use Hello, World;}"), st!(0,36, Stmt_::Decl(Decl::Trait(vec![], "HelloWorld".into(), vec![
         Member::TraitUse(vec![Path::identifier(false, "Hello".into()), Path::identifier(false, "World".into())], vec![])
     ]))));
 }
 
 #[test]
 fn parse_interface_decl() {
-    assert_eq!(process_stmt("interface ITest {}"), st!(0,18, Stmt_::Decl(Decl::Interface("ITest".into(), vec![], vec![]))));
+    assert_eq!(process_stmt("interface ITest {}"), st!(0,18, Stmt_::Decl(Decl::Interface(vec![], "ITest".into(), vec![], vec![]))));
     assert_eq!(process_stmt("interface ITest { public function test(); }"), st!(0,43, Stmt_::Decl(
-        Decl::Interface("ITest".into(), vec![], vec![ Member::Method(MemberModifiers::new(&[MemberModifier::Public]),
-            "test".into(), FunctionDecl {params: vec![], body: None, usev: vec![], ret_ref: false})
+        Decl::Interface(vec![], "ITest".into(), vec![], vec![ Member::Method(MemberModifiers::new(&[MemberModifier::Public]),
+            "test".into(), FunctionDecl {params: vec![], body: None, usev: vec![], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None})
         ])
     )));
 }
@@ -391,7 +619,7 @@ fn parse_class_use_trait_complex() {
         }
     }";
     assert_eq!(process_stmt(code), st!(0,163, Stmt_::Decl(Decl::Class(ClassDecl {
-        cmod: ClassModifiers::none(), name: "Aliased_Talker".into(), base_class: None, implements: vec![], members: vec![
+        attributes: vec![], cmod: ClassModifiers::none(), name: "Aliased_Talker".into(), base_class: None, implements: vec![], members: vec![
             Member::TraitUse(vec![Path::identifier(false, "A".into()), Path::identifier(false, "B".into())], vec![
                 TraitUse::InsteadOf(Path::identifier(false, "B".into()), "smallTalk".into(), vec![Path::identifier(false, "A".into())]),
                 TraitUse::InsteadOf(Path::identifier(false, "A".into()), "bigTalk".into(), vec![Path::identifier(false, "B".into())]),
@@ -401,6 +629,24 @@ fn parse_class_use_trait_complex() {
     }))));
 }
 
+#[test]
+fn parse_class_trait_alias_visibility() {
+    assert_eq!(process_stmt("class T { use A { a as protected; b as private c; } }"), st!(0,53, Stmt_::Decl(Decl::Class(ClassDecl {
+        attributes: vec![], cmod: ClassModifiers::none(), name: "T".into(), base_class: None, implements: vec![], members: vec![
+            Member::TraitUse(vec![Path::identifier(false, "A".into())], vec![
+                TraitUse::As(None, "a".into(), MemberModifiers::new(&[MemberModifier::Protected]), None),
+                TraitUse::As(None, "b".into(), MemberModifiers::new(&[MemberModifier::Private]), Some("c".into())),
+            ])
+        ]
+    }))));
+    assert!(Parser::parse_str("<?php class T { use A { a as; } }").is_err());
+    let err = Parser::parse_str("<?php class T { use A { a as public private; } }").unwrap_err();
+    assert_eq!(err.error_message(None), "Multiple access type modifiers are not allowed");
+    assert_eq!(err.span(), mk_span(36usize, 43usize));
+    let err = Parser::parse_str("<?php class T { use A { a as static b; } }").unwrap_err();
+    assert_eq!(err.error_message(None), "Cannot use 'static' as method modifier");
+}
+
 #[test]
 fn parse_static_decl() {
     assert_eq!(process_stmt("static $t=true;"), st!(0,15, Stmt_::Decl(Decl::StaticVars(vec![ ("t".into(), Some(enb!(10,14, constant!(true)))) ]))));
@@ -414,11 +660,29 @@ fn parse_global_decl() {
 #[test]
 fn parse_stmt_closure_use() {
     assert_eq!(process_stmt("return function () use ($t) {};"), st!(0,31, Stmt_::Return(Some(eb!(7,30, Expr_::Function(FunctionDecl {
-        params: vec![], body: Some(Block(vec![])), usev: vec![(false, "t".into())], ret_ref: false,
+        params: vec![], body: Some(Block(vec![])), usev: vec![(false, "t".into())], ret_ref: false, is_static: false, attributes: vec![], ret_ty: None,
     }))))));
 }
 
 #[test]
 fn parse_namespace_decl() {
-    assert_eq!(process_stmt("namespace Foo\\Bar;"), st!(0,17, Stmt_::Decl(Decl::Namespace(Path::ns_identifier(false, "Foo".into(), "Bar".into())))));
+    assert_eq!(process_stmt("namespace Foo\\Bar;"), st!(0,18, Stmt_::Decl(Decl::Namespace(Some(Path::ns_identifier(false, "Foo".into(), "Bar".into())), Block(vec![]), false))));
+}
+
+#[test]
+fn parse_stmt_const_decl() {
+    assert_eq!(process_stmt("const A = 1, B = 2;"), st!(0,19, Stmt_::Decl(Decl::GlobalConstants(vec![
+        ("A".into(), enb!(10,11, Expr_::Int(1))), ("B".into(), enb!(17,18, Expr_::Int(2))),
+    ]))));
+}
+
+#[test]
+fn parse_stmt_declare() {
+    assert_eq!(process_stmt("declare(strict_types=1);"), st!(0,24, Stmt_::Declare(vec![ ("strict_types".into(), enb!(21,22, Expr_::Int(1))) ], None, BlockSyntax::Normal)));
+    assert_eq!(process_stmt("declare(ticks=1) { echo 1; }"), st!(0,28, Stmt_::Declare(vec![ ("ticks".into(), enb!(14,15, Expr_::Int(1))) ],
+        Some(Block(vec![ st!(19,26, Stmt_::Echo(vec![ enb!(24,25, Expr_::Int(1)) ])) ])), BlockSyntax::Normal,
+    )));
+    assert_eq!(process_stmt("declare(ticks=1, encoding='UTF-8'): echo 1; enddeclare;"), st!(0,55, Stmt_::Declare(vec![
+        ("ticks".into(), enb!(14,15, Expr_::Int(1))), ("encoding".into(), enb!(26,33, Expr_::String("UTF-8".into()))),
+    ], Some(Block(vec![ st!(36,43, Stmt_::Echo(vec![ enb!(41,42, Expr_::Int(1)) ])) ])), BlockSyntax::Alternative)));
 }
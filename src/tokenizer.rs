@@ -40,9 +40,25 @@ enum State {
     InScripting,
     LookingForProperty,
     EmitQueue,
+    /// after __halt_compiler, only scanning the `();` before the data
+    HaltCompiler,
+    /// the remaining input is returned as HaltCompilerData
+    HaltCompilerData,
     Done,
 }
 
+/// the kind of string literal an escape sequence occurs in
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EscapeMode {
+    /// only \' and \\ are escapes
+    SingleQuoted,
+    DoubleQuoted,
+    /// like DoubleQuoted, but \" is kept as is
+    HereDoc,
+    /// like HereDoc, but \` is an escape
+    Backquote,
+}
+
 pub struct Tokenizer<'a> {
     code: &'a str,
     /// whether to support short tags, equal to CG(short_tags)
@@ -134,7 +150,8 @@ trait StrStartsWithCI {
 impl<'a> StrStartsWithCI for &'a str {
     #[inline]
     fn starts_with_ci(&self, s: &str) -> bool {
-        self.chars().take(s.len()).collect::<String>() == s.to_lowercase()
+        // keywords are ASCII, so comparing the bytes is enough
+        self.len() >= s.len() && self.as_bytes()[..s.len()].eq_ignore_ascii_case(s.as_bytes())
     }
 }
 
@@ -475,54 +492,82 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    fn str_escape(&mut self, bytes: &mut Vec<u8>, sq: bool) -> Result<(), SyntaxError> {
-        let chr = match (self.input().chars().nth(1), sq) {
-            (Some('n'), false) => Some(b'\n'),
-            (Some('r'), false) => Some(b'\r'),
-            (Some('t'), false) => Some(b'\t'),
-            (Some('f'), false) => Some(b'\x0C'),
-            (Some('v'), false) => Some(b'\x0B'),
-            (Some('e'), false) => unimplemented!(),
-            (Some('"'), false) => Some(b'"'),
-            (Some('\''), true) => Some(b'\''),
-            (Some('\\'), _) => Some(b'\\'),
-            (Some('$'), false) => Some(b'$'),
-            (Some(x @ 'x'), false) |
-            (Some(x @ 'X'), false) => {
-                // read up to 2 hex characters, on 0 add \x to bytes
-                let mut end_idx = 0;
-                for i in 2..4 {
-                    match self.input().chars().nth(i) {
-                        Some('a'...'z') | Some('A'...'Z') | Some('0'...'9') => end_idx = i,
-                        _ => break,
-                    }
-                }
-                if end_idx == 0 {
-                    bytes.push(b'\\');
-                    Some(x as u8)
-                } else {
-                    let start_pos = self.input().char_indices().nth(2).unwrap().0;
-                    let end_pos = self.input().char_indices().nth(end_idx + 1).unwrap().0;
-                    let byte = u8::from_str_radix(&self.input()[start_pos..end_pos], 16).unwrap();
-                    bytes.push(byte);
-                    self.advance_chars(1 + end_idx);
-                    return Ok(());
-                }
+    fn str_escape(&mut self, bytes: &mut Vec<u8>, mode: EscapeMode) -> Result<(), SyntaxError> {
+        let dq = mode != EscapeMode::SingleQuoted;
+        let chr = match self.input().chars().nth(1) {
+            Some('n') if dq => b'\n',
+            Some('r') if dq => b'\r',
+            Some('t') if dq => b'\t',
+            Some('f') if dq => b'\x0C',
+            Some('v') if dq => b'\x0B',
+            Some('e') if dq => b'\x1B',
+            Some('"') if mode == EscapeMode::DoubleQuoted => b'"',
+            Some('`') if mode == EscapeMode::Backquote => b'`',
+            Some('\'') if !dq => b'\'',
+            Some('\\') => b'\\',
+            Some('$') if dq => b'$',
+            // up to 2 hex characters
+            Some('x') | Some('X') if dq && self.input().bytes().nth(2).map_or(false, |b| b.is_ascii_hexdigit()) => {
+                let len = self.input().bytes().skip(2).take(2).take_while(|b| b.is_ascii_hexdigit()).count();
+                let byte = u8::from_str_radix(&self.input()[2..2 + len], 16).unwrap();
+                bytes.push(byte);
+                self.advance_bytes(2 + len);
+                return Ok(());
+            }
+            // up to 3 octal characters, values above \377 overflow like in PHP
+            Some('0'...'7') if dq => {
+                let len = self.input().bytes().skip(1).take(3).take_while(|b| b'0' <= *b && *b <= b'7').count();
+                let value = u32::from_str_radix(&self.input()[1..1 + len], 8).unwrap();
+                bytes.push(value as u8);
+                self.advance_bytes(1 + len);
+                return Ok(());
             }
-            (Some('u'), false) => unimplemented!(),
-            (Some(x), _) => {
+            Some('u') if dq && self.input()[2..].starts_with('{') => return self.str_unicode_escape(bytes),
+            // not an escape sequence, keep the backslash
+            Some(x) => {
                 bytes.push(b'\\');
                 let mut tmp_str = String::new();
                 tmp_str.push(x);
                 bytes.extend(tmp_str.as_bytes());
-                None
+                self.advance_chars(2);
+                return Ok(());
             }
-            _ => return Err(SyntaxError::Unterminated("string escape sequence", mk_span(self.input_pos(), self.input_pos() + 1))),
+            None => return Err(SyntaxError::Unterminated("string escape sequence", mk_span(self.input_pos(), self.input_pos() + 1))),
         };
-        self.advance_chars(2);
-        if let Some(chr) = chr {
-            bytes.push(chr);
+        self.advance_bytes(2);
+        bytes.push(chr);
+        Ok(())
+    }
+
+    /// decodes a \u{codepoint} escape sequence to UTF-8
+    fn str_unicode_escape(&mut self, bytes: &mut Vec<u8>) -> Result<(), SyntaxError> {
+        let start = self.input_pos();
+        let len = self.input()[3..].bytes().take_while(|b| b.is_ascii_hexdigit()).count();
+        if len == 0 || !self.input()[3 + len..].starts_with('}') {
+            return Err(SyntaxError::Invalid("Invalid UTF-8 codepoint escape sequence", mk_span(start, start + 3 + len)));
         }
+        let codepoint = match u32::from_str_radix(&self.input()[3..3 + len], 16) {
+            Ok(codepoint) if codepoint <= 0x10FFFF => codepoint,
+            _ => return Err(SyntaxError::Invalid("Invalid UTF-8 codepoint escape sequence: Codepoint too large",
+                mk_span(start, start + 4 + len))),
+        };
+        // encoded manually, since PHP also accepts surrogates which aren't a valid char
+        if codepoint < 0x80 {
+            bytes.push(codepoint as u8);
+        } else if codepoint < 0x800 {
+            bytes.push(0xC0 | (codepoint >> 6) as u8);
+            bytes.push(0x80 | (codepoint & 0x3F) as u8);
+        } else if codepoint < 0x10000 {
+            bytes.push(0xE0 | (codepoint >> 12) as u8);
+            bytes.push(0x80 | ((codepoint >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (codepoint & 0x3F) as u8);
+        } else {
+            bytes.push(0xF0 | (codepoint >> 18) as u8);
+            bytes.push(0x80 | ((codepoint >> 12) & 0x3F) as u8);
+            bytes.push(0x80 | ((codepoint >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (codepoint & 0x3F) as u8);
+        }
+        self.advance_bytes(4 + len);
         Ok(())
     }
 
@@ -533,7 +578,8 @@ impl<'a> Tokenizer<'a> {
                                 bytes: Vec<u8>,
                                 parts: Vec<TokenSpan>)
                                 -> TokenSpan {
-        let (start_pos, end_pos) = (start_tok.1.start, end_tok.1.end);
+        // the remaining bytes are located between the last part and the end token
+        let (start_pos, end_pos) = (parts.last().map_or(start_tok.1.end, |part| part.1.end), end_tok.1.start);
         self.queue.push(end_tok);
         if !bytes.is_empty() {
             let ret_token = match String::from_utf8(bytes) {
@@ -580,7 +626,7 @@ impl<'a> Tokenizer<'a> {
                     self.state.next_line();
                     bytes.push(b'\n');
                 }
-                Some('\\') => try!(self.str_escape(&mut bytes, true)),
+                Some('\\') => try!(self.str_escape(&mut bytes, EscapeMode::SingleQuoted)),
                 Some('\'') => {
                     self.advance_bytes(1);
                     break;
@@ -600,7 +646,7 @@ impl<'a> Tokenizer<'a> {
         Ok(TokenSpan(ret_token, span))
     }
 
-    fn str_variable(&mut self, bytes: &mut Vec<u8>, parts: &mut Vec<TokenSpan>) {
+    fn str_variable(&mut self, bytes: &mut Vec<u8>, parts: &mut Vec<TokenSpan>) -> Result<(), SyntaxError> {
         self.advance_bytes(1);
         // T_DOLLAR_OPEN_CURLY_BRACES ${ ... } syntax (simple = DollarCurlyBraces, complex = str_block)
         if self.input().starts_with('{') {
@@ -619,21 +665,39 @@ impl<'a> Tokenizer<'a> {
             assert_eq!(parts[next_part].0, Token::CurlyBracesOpen);
             parts[next_part].1.start -= 1;
             parts[next_part].0 = Token::DollarCurlyBracesOpen;
-            return;
+            return Ok(());
         }
         // match variable
         if let Some((label, span)) = self._label().map(|(x, span)| (self.interner.intern(x), span)) {
             let mut tmp_parts = vec![];
             // match var_offset
             if self.input().starts_with('[') {
-                unimplemented!();
+                let bak_pos = self.input_pos();
+                self.advance_bytes(1);
+                match self.str_var_offset() {
+                    Some(offset) => {
+                        tmp_parts.push(TokenSpan(Token::SquareBracketOpen, mk_span(bak_pos, bak_pos+1)));
+                        tmp_parts.push(offset);
+                        tmp_parts.push(TokenSpan(Token::SquareBracketClose, mk_span(self.input_pos()-1, self.input_pos())));
+                    }
+                    // as in PHP, "$a[" always starts an offset, so anything unexpected is an error (e.g. "$a[ 0]")
+                    None => {
+                        let end = self.input_pos() + self.input().chars().next().map_or(0, char::len_utf8);
+                        return Err(SyntaxError::Invalid("Invalid variable offset in string", mk_span(bak_pos, end)));
+                    }
+                }
             }
-            // match object access (only $var->label supported in PHP)
-            else if self.input().starts_with("->") {
+            // match object access (only $var->label and $var?->label supported in PHP)
+            else if self.input().starts_with("->") || self.input().starts_with("?->") {
                 let bak_pos = self.input_pos();
-                self.advance_bytes(2);
+                let (op, len) = if self.input().starts_with('?') {
+                    (Token::NullsafeObjectOp, 3)
+                } else {
+                    (Token::ObjectOp, 2)
+                };
+                self.advance_bytes(len);
                 if let Some((property, span)) = self._label().map(|(x, span)| (self.interner.intern(x), span)) {
-                    tmp_parts.push(TokenSpan(Token::ObjectOp, mk_span(bak_pos, bak_pos+1)));
+                    tmp_parts.push(TokenSpan(op, mk_span(bak_pos, bak_pos+len)));
                     tmp_parts.push(TokenSpan(Token::String(property), mk_span(span.start, span.end)));
                 } else {
                     self.state.src_pos = bak_pos;
@@ -654,6 +718,57 @@ impl<'a> Tokenizer<'a> {
         } else {
             bytes.push(b'$');
         }
+        Ok(())
+    }
+
+    /// match the offset of a simple array access within a string ("$a[0]", "$a[key]", "$a[$b]"), including the closing bracket
+    fn str_var_offset(&mut self) -> Option<TokenSpan> {
+        let start = self.input_pos();
+        let token = if self.input().starts_with('$') {
+            self.advance_bytes(1);
+            match self._label() {
+                Some((name, _)) => Token::Variable(self.interner.intern(name)),
+                None => return None,
+            }
+        } else if let Some((name, _)) = self._label() {
+            Token::ConstantEncapsedString(self.interner.intern(name))
+        } else {
+            let negative = self.input().starts_with('-');
+            let sign_len = if negative { 1 } else { 0 };
+            let (len, is_decimal) = {
+                let digits = &self.input()[sign_len..];
+                let prefixed_len = |pred: fn(&u8) -> bool| {
+                    match digits.bytes().skip(2).take_while(pred).count() {
+                        0 => 0,
+                        n => n + 2,
+                    }
+                };
+                if digits.starts_with("0x") || digits.starts_with("0X") {
+                    (prefixed_len(|b| b.is_ascii_hexdigit()), false)
+                } else if digits.starts_with("0b") || digits.starts_with("0B") {
+                    (prefixed_len(|b| *b == b'0' || *b == b'1'), false)
+                } else {
+                    (digits.bytes().take_while(|b| b.is_ascii_digit()).count(), true)
+                }
+            };
+            if len == 0 {
+                return None;
+            }
+            let raw = self.advance_bytes(sign_len + len);
+            let digits = &raw[sign_len..];
+            // only canonical decimal integers are integer keys, everything else ("01", "0x1", "-0") stays a string
+            let canonical = is_decimal && (digits == "0" && !negative || !digits.starts_with('0'));
+            match raw.parse::<i64>() {
+                Ok(num) if canonical => Token::Int(num),
+                _ => Token::ConstantEncapsedString(self.interner.intern(raw)),
+            }
+        };
+        let end = self.input_pos();
+        if !self.input().starts_with(']') {
+            return None;
+        }
+        self.advance_bytes(1);
+        Some(TokenSpan(token, mk_span(start, end)))
     }
 
     fn str_block(&mut self,
@@ -705,7 +820,7 @@ impl<'a> Tokenizer<'a> {
         } else {
             return Err(SyntaxError::None);
         }
-        // valid escapes: \n \r \t \f \v \e \" \\ \$ \x \X \u{unicode}
+        // valid escapes: \n \r \t \f \v \e \" \\ \$ \[0-7]{1,3} \x \X \u{unicode}
 
         // repeatedly progress until we encounter an escape sequence (or end)
         let mut parts = vec![];
@@ -725,12 +840,12 @@ impl<'a> Tokenizer<'a> {
                     self.state.next_line();
                     bytes.push(b'\n');
                 }
-                Some('\\') => try!(self.str_escape(&mut bytes, false)),
+                Some('\\') => try!(self.str_escape(&mut bytes, EscapeMode::DoubleQuoted)),
                 Some('"') => {
                     self.advance_bytes(1);
                     break;
                 }
-                Some('$') => self.str_variable(&mut bytes, &mut parts),
+                Some('$') => try!(self.str_variable(&mut bytes, &mut parts)),
                 // match {$<IN_SCRIPTING>} block
                 Some('{') => self.str_block(&mut bytes, &mut parts, true),
                 _ => {
@@ -765,7 +880,7 @@ impl<'a> Tokenizer<'a> {
         loop {
             let end_pos = match self.input()
                 .chars()
-                .position(|x| x == '\\' || x == '"' || x == '$' || x == '\n' || x == '{') {
+                .position(|x| x == '\\' || x == '`' || x == '$' || x == '\n' || x == '{') {
                 Some(end_pos) => end_pos,
                 None => self.input().chars().count() - 1,
             };
@@ -777,11 +892,12 @@ impl<'a> Tokenizer<'a> {
                     self.state.next_line();
                     bytes.push(b'\n');
                 }
+                Some('\\') => try!(self.str_escape(&mut bytes, EscapeMode::Backquote)),
                 Some('`') => {
                     self.advance_bytes(1);
                     break;
                 }
-                Some('$') => self.str_variable(&mut bytes, &mut parts),
+                Some('$') => try!(self.str_variable(&mut bytes, &mut parts)),
                 // match {$<IN_SCRIPTING>} block
                 Some('{') => self.str_block(&mut bytes, &mut parts, true),
                 _ => {
@@ -901,8 +1017,12 @@ impl<'a> Tokenizer<'a> {
                         bytes.push(b'\n');
                     }
                 }
-                (Some('\\'), _) => try!(self.str_escape(&mut bytes, is_now_doc)),
-                (Some('$'), false) => self.str_variable(&mut bytes, &mut parts),
+                (Some('\\'), false) => try!(self.str_escape(&mut bytes, EscapeMode::HereDoc)),
+                (Some('\\'), true) => {
+                    self.advance_bytes(1);
+                    bytes.push(b'\\');
+                }
+                (Some('$'), false) => try!(self.str_variable(&mut bytes, &mut parts)),
                 (Some('{'), false) => self.str_block(&mut bytes, &mut parts, true),
                 _ => {
                     let old_pos = self.input_pos();
@@ -931,8 +1051,10 @@ impl<'a> Tokenizer<'a> {
     pub fn match_comments(&mut self) -> Result<TokenSpan, SyntaxError> {
         let old_pos = self.input_pos();
         let mut doc_comment = false;
-        // single line comment
-        let start_tokens_count = if self.input().starts_with('#') {
+        // single line comment, `#[` starts an attribute instead
+        let start_tokens_count = if self.input().starts_with("#[") {
+            0
+        } else if self.input().starts_with('#') {
             1
         } else if self.input().starts_with("//") {
             2
@@ -989,10 +1111,18 @@ impl<'a> Tokenizer<'a> {
     pub fn next_token(&mut self) -> Result<TokenSpan, SyntaxError> {
         loop {
             let ret = match self.state.state {
-                State::Done | State::DoNothing => Err(SyntaxError::None),
+                State::DoNothing => Err(SyntaxError::None),
+                State::Done => Ok(TokenSpan(Token::End, mk_span(self.code.len(), self.code.len()))),
                 State::Initial => self.initial_token(),
                 State::InScripting => self.in_scripting_token(),
                 State::LookingForProperty => self.looking_for_property_token(),
+                State::HaltCompiler => self.halt_compiler_token(),
+                State::HaltCompilerData => {
+                    let start_pos = self.input_pos();
+                    let data = self.advance_bytes(self.input().len());
+                    self.state.state = State::Done;
+                    Ok(TokenSpan(Token::HaltCompilerData(Rc::new(data.as_bytes().to_vec())), mk_span(start_pos, self.code.len())))
+                }
                 /// this state allows returning multiple tokens (for e.g. string fragments)
                 State::EmitQueue => {
                     match self.queue.pop() {
@@ -1099,6 +1229,9 @@ impl<'a> Tokenizer<'a> {
         }
         if let Some(ret) = longest.1 {
             self.state.src_pos = longest.0 as usize;
+            if let Token::HaltCompiler = ret.0 {
+                self.state.state = State::HaltCompiler;
+            }
             return Ok(ret);
         }
         if let Some(err) = longest_err.1 {
@@ -1113,6 +1246,7 @@ impl<'a> Tokenizer<'a> {
         ret_token!(match_token!(self, Exit));
         ret_token!(match_token_alias!(self, "die", Exit));
         ret_token!(match_token!(self, Function));
+        ret_token!(match_token!(self, Fn));
         ret_token!(match_token!(self, Const));
         ret_token!(match_token!(self, Return));
         ret_token!({
@@ -1156,6 +1290,7 @@ impl<'a> Tokenizer<'a> {
         ret_token!(match_token!(self, As));
         ret_token!(match_token!(self, Switch));
         ret_token!(match_token!(self, EndSwitch));
+        ret_token!(match_token!(self, Match));
         ret_token!(match_token!(self, Case));
         ret_token!(match_token!(self, Default));
         ret_token!(match_token!(self, Break));
@@ -1169,9 +1304,12 @@ impl<'a> Tokenizer<'a> {
         ret_token!(match_token!(self, Extends));
         ret_token!(match_token!(self, Implements));
         ret_token!(match_token!(self, ObjectOp, state <- LookingForProperty));
+        ret_token!(match_token!(self, NullsafeObjectOp, state <- LookingForProperty));
         ret_token!(match_token!(self, ScopeOp));
         ret_token!(match_token!(self, NsSeparator));
         ret_token!(match_token!(self, Ellipsis));
+        ret_token!(match_token!(self, AttributeStart));
+        ret_token!(match_token!(self, CoalesceEqual));
         ret_token!(match_token!(self, Coalesce));
         ret_token!(match_token!(self, New));
         ret_token!(match_token!(self, Clone));
@@ -1181,16 +1319,17 @@ impl<'a> Tokenizer<'a> {
         if self.input().starts_with('(') {
             #[inline]
             fn try_determine_cast_type(self_: &mut Tokenizer) -> Result<TokenSpan, SyntaxError> {
+                // longer spellings first, since "int" is a prefix of "integer"
+                ret_token!(match_token!(self_, CastInteger));
                 ret_token!(match_token!(self_, CastInt));
-                ret_token!(match_token_alias!(self_, "int", CastInt));
-                ret_token!(match_token_alias!(self_, "real", CastDouble));
+                ret_token!(match_token!(self_, CastReal));
                 ret_token!(match_token!(self_, CastDouble));
-                ret_token!(match_token_alias!(self_, "float", CastDouble));
+                ret_token!(match_token!(self_, CastFloat));
                 ret_token!(match_token!(self_, CastString));
-                ret_token!(match_token_alias!(self_, "binary", CastString));
+                ret_token!(match_token!(self_, CastBinary));
                 ret_token!(match_token!(self_, CastArray));
                 ret_token!(match_token!(self_, CastObject));
-                ret_token!(match_token_alias!(self_, "boolean", CastBool));
+                ret_token!(match_token!(self_, CastBoolean));
                 ret_token!(match_token!(self_, CastBool));
                 ret_token!(match_token!(self_, CastUnset));
                 Err(SyntaxError::None)
@@ -1282,6 +1421,19 @@ impl<'a> Tokenizer<'a> {
         Err(SyntaxError::UnknownCharacter(mk_span(self.input_pos(), self.input_pos() + 1)))
     }
 
+    /// token-scanner after __halt_compiler, which only matches `(`, `)` and the terminating `;` or `?>`
+    fn halt_compiler_token(&mut self) -> Result<TokenSpan, SyntaxError> {
+        self.whitespace();
+        if self.input().is_empty() {
+            return Ok(TokenSpan(Token::End, mk_span(self.code.len(), self.code.len())));
+        }
+        ret_token!(match_token_alias!(self, "(", ParenthesesOpen));
+        ret_token!(match_token_alias!(self, ")", ParenthesesClose));
+        ret_token!(match_token_alias!(self, ";", SemiColon, state = HaltCompilerData));
+        ret_token!(match_token!(self, CloseTag, state = HaltCompilerData));
+        Err(SyntaxError::UnknownCharacter(mk_span(self.input_pos(), self.input_pos() + 1)))
+    }
+
     /// token-scanner for looking-for-property state
     fn looking_for_property_token(&mut self) -> Result<TokenSpan, SyntaxError> {
         self.whitespace();
@@ -1332,11 +1484,68 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn nullsafe_object_operator() {
+        let mut tokenizer = Tokenizer::new("<?php $a?->b?->c ?>");
+        assert_eq!(get_n_tokens(&mut tokenizer, 6), vec![Ok(Token::OpenTag), Ok(Token::Variable("a".into())), Ok(Token::NullsafeObjectOp),
+            Ok(Token::String("b".into())), Ok(Token::NullsafeObjectOp), Ok(Token::String("c".into()))
+        ]);
+        let mut tokenizer = Tokenizer::new("<?php \"$a?->b\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 6), vec![Ok(Token::OpenTag), Ok(Token::DoubleQuote), Ok(Token::Variable("a".into())),
+            Ok(Token::NullsafeObjectOp), Ok(Token::String("b".into())), Ok(Token::DoubleQuote),
+        ]);
+    }
+
+    #[test]
+    fn halt_compiler_data() {
+        let mut tokenizer = Tokenizer::new("<?php __halt_compiler(); ?> $a");
+        assert_eq!(get_n_tokens(&mut tokenizer, 7), vec![Ok(Token::OpenTag), Ok(Token::HaltCompiler), Ok(Token::ParenthesesOpen),
+            Ok(Token::ParenthesesClose), Ok(Token::SemiColon), Ok(Token::HaltCompilerData(Rc::new(b" ?> $a".to_vec()))), Ok(Token::End)
+        ]);
+    }
+
+    #[test]
+    fn fn_keyword() {
+        let mut tokenizer = Tokenizer::new("<?php fn fnord function");
+        assert_eq!(get_n_tokens(&mut tokenizer, 4),
+                   vec![Ok(Token::OpenTag), Ok(Token::Fn), Ok(Token::String("fnord".into())), Ok(Token::Function)]);
+    }
+
+    #[test]
+    fn attribute_start() {
+        let mut tokenizer = Tokenizer::new("<?php #[A] # comment\n$a");
+        assert_eq!(get_n_tokens(&mut tokenizer, 6), vec![Ok(Token::OpenTag), Ok(Token::AttributeStart), Ok(Token::String("A".into())),
+            Ok(Token::SquareBracketClose), Ok(Token::Comment(" comment".into())), Ok(Token::Variable("a".into())),
+        ]);
+    }
+
+    #[test]
+    fn match_keyword() {
+        let mut tokenizer = Tokenizer::new("<?php match matches");
+        assert_eq!(get_n_tokens(&mut tokenizer, 3),
+                   vec![Ok(Token::OpenTag), Ok(Token::Match), Ok(Token::String("matches".into()))]);
+    }
+
     #[test]
     fn simple_cast() {
         let mut tokenizer = Tokenizer::new("<?php  (string) ?>");
         assert_eq!(get_n_tokens(&mut tokenizer, 3),
                    vec![Ok(Token::OpenTag), Ok(Token::CastString), Ok(Token::CloseTag)]);
+        let mut tokenizer = Tokenizer::new("<?php (integer) (int)( real )(boolean)(bool)(binary)");
+        assert_eq!(get_n_tokens(&mut tokenizer, 7),
+                   vec![Ok(Token::OpenTag), Ok(Token::CastInteger), Ok(Token::CastInt), Ok(Token::CastReal), Ok(Token::CastBoolean),
+                        Ok(Token::CastBool), Ok(Token::CastBinary)]);
+        let mut tokenizer = Tokenizer::new("<?php (INT)$x (Integer)$x ( int )$x (\tSTRING\t)");
+        assert_eq!(get_n_tokens(&mut tokenizer, 8),
+                   vec![Ok(Token::OpenTag), Ok(Token::CastInt), Ok(Token::Variable("x".into())), Ok(Token::CastInteger),
+                        Ok(Token::Variable("x".into())), Ok(Token::CastInt), Ok(Token::Variable("x".into())), Ok(Token::CastString)]);
+    }
+
+    #[test]
+    fn keyword_case_insensitive() {
+        let mut tokenizer = Tokenizer::new("<?PHP ECHO New Foreach AS");
+        assert_eq!(get_n_tokens(&mut tokenizer, 5),
+                   vec![Ok(Token::OpenTag), Ok(Token::Echo), Ok(Token::New), Ok(Token::Foreach), Ok(Token::As)]);
     }
 
     #[test]
@@ -1426,6 +1635,28 @@ mod tests {
                         Ok(Token::ConstantEncapsedString("转\t注\t字".into()))]);
     }
 
+    #[test]
+    fn dq_string_escapes() {
+        let mut tokenizer = Tokenizer::new(r#"<?php "\e\u{41}\u{e9}\u{1F600}\101\400\x4a\xg\u\q""#);
+        assert_eq!(get_n_tokens(&mut tokenizer, 3),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::DoubleQuote),
+                        Ok(Token::ConstantEncapsedString("\x1BAé😀A\0J\\xg\\u\\q".into()))]);
+        let mut tokenizer = Tokenizer::new(r#"<?php "\u{D800}""#);
+        assert_eq!(get_n_tokens(&mut tokenizer, 3),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::DoubleQuote),
+                        Ok(Token::BinaryCharSequence(Rc::new(vec![0xED, 0xA0, 0x80])))]);
+        let mut tokenizer = Tokenizer::new(r#"<?php "\u{12""#);
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid UTF-8 codepoint escape sequence", mk_span(7usize, 12usize)))]);
+        let mut tokenizer = Tokenizer::new(r#"<?php "\u{110000}""#);
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid UTF-8 codepoint escape sequence: Codepoint too large", mk_span(7usize, 17usize)))]);
+    }
+
     #[test]
     fn sq_string() {
         let mut tokenizer = Tokenizer::new("<?php 'testhallo\\nwelt \\'g\\''");
@@ -1470,6 +1701,51 @@ mod tests {
                         Ok(Token::CurlyBracesClose)]);
     }
 
+    #[test]
+    fn dq_string_var_offset() {
+        let mut tokenizer = Tokenizer::new("<?php \"$a[0]$a[key]$a[$b]\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 14),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::DoubleQuote),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::SquareBracketOpen),
+                        Ok(Token::Int(0)),
+                        Ok(Token::SquareBracketClose),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::SquareBracketOpen),
+                        Ok(Token::ConstantEncapsedString("key".into())),
+                        Ok(Token::SquareBracketClose),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::SquareBracketOpen),
+                        Ok(Token::Variable("b".into())),
+                        Ok(Token::SquareBracketClose)]);
+        let mut tokenizer = Tokenizer::new("<?php \"$a[-1]$a[01]$a[0x1A]\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 14)[4..],
+                   [Ok(Token::Int(-1)),
+                    Ok(Token::SquareBracketClose),
+                    Ok(Token::Variable("a".into())),
+                    Ok(Token::SquareBracketOpen),
+                    Ok(Token::ConstantEncapsedString("01".into())),
+                    Ok(Token::SquareBracketClose),
+                    Ok(Token::Variable("a".into())),
+                    Ok(Token::SquareBracketOpen),
+                    Ok(Token::ConstantEncapsedString("0x1A".into())),
+                    Ok(Token::SquareBracketClose)]);
+        // a malformed offset is an error
+        let mut tokenizer = Tokenizer::new("<?php \"$a[ 0]\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid variable offset in string", mk_span(9usize, 11usize)))]);
+        let mut tokenizer = Tokenizer::new("<?php \"$a[0x]\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid variable offset in string", mk_span(9usize, 11usize)))]);
+        let mut tokenizer = Tokenizer::new("<?php \"$a[b c]\"");
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid variable offset in string", mk_span(9usize, 12usize)))]);
+    }
+
     #[test]
     fn backquote() {
         let mut tokenizer = Tokenizer::new("<?php `ab $world cd`");
@@ -1480,6 +1756,12 @@ mod tests {
                         Ok(Token::Variable("world".into())),
                         Ok(Token::ConstantEncapsedString(" cd".into())),
                         Ok(Token::Backquote)]);
+        let mut tokenizer = Tokenizer::new(r#"<?php `a \` "b" \$c`"#);
+        assert_eq!(get_n_tokens(&mut tokenizer, 4),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::Backquote),
+                        Ok(Token::ConstantEncapsedString("a ` \"b\" $c".into())),
+                        Ok(Token::Backquote)]);
     }
 
     #[test]
@@ -1498,6 +1780,44 @@ mod tests {
                         Ok(Token::HereDocEnd)]);
     }
 
+    #[test]
+    fn heredoc_escapes() {
+        let mut tokenizer = Tokenizer::new("<?php <<<EOT\n\\\"\\e\\$\nEOT;\n");
+        assert_eq!(get_n_tokens(&mut tokenizer, 3),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::HereDocStart),
+                        Ok(Token::ConstantEncapsedString("\\\"\x1B$".into()))]);
+        let mut tokenizer = Tokenizer::new("<?php <<<'EOT'\n\\\\\\'\\t\nEOT;\n");
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::ConstantEncapsedString("\\\\\\'\\t".into()))]);
+    }
+
+    #[test]
+    fn heredoc_variables() {
+        let mut tokenizer = Tokenizer::new("<?php <<<EOT\n$a[0] $a[key] $a->b\nEOT;\n");
+        assert_eq!(get_n_tokens(&mut tokenizer, 15),
+                   vec![Ok(Token::OpenTag),
+                        Ok(Token::HereDocStart),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::SquareBracketOpen),
+                        Ok(Token::Int(0)),
+                        Ok(Token::SquareBracketClose),
+                        Ok(Token::ConstantEncapsedString(" ".into())),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::SquareBracketOpen),
+                        Ok(Token::ConstantEncapsedString("key".into())),
+                        Ok(Token::SquareBracketClose),
+                        Ok(Token::ConstantEncapsedString(" ".into())),
+                        Ok(Token::Variable("a".into())),
+                        Ok(Token::ObjectOp),
+                        Ok(Token::String("b".into()))]);
+        let mut tokenizer = Tokenizer::new("<?php <<<EOT\n$a[ 0]\nEOT;\n");
+        assert_eq!(get_n_tokens(&mut tokenizer, 2),
+                   vec![Ok(Token::OpenTag),
+                        Err(SyntaxError::Invalid("Invalid variable offset in string", mk_span(15usize, 17usize)))]);
+    }
+
     #[test]
     fn nowdoc() {
         let mut tokenizer = Tokenizer::new("<?php <<<'EOT'\nte\\tst\nEOT;\n");
@@ -31,6 +31,8 @@ pub enum SyntaxError {
     None,
     Unterminated(&'static str, Span),
     UnknownCharacter(Span),
+    /// a construct which was recognized but is invalid (e.g. a malformed escape sequence)
+    Invalid(&'static str, Span),
 }
 
 impl SyntaxError {
@@ -38,7 +40,8 @@ impl SyntaxError {
         match *self {
             SyntaxError::None => unimplemented!(),
             SyntaxError::Unterminated(_, ref span) |
-            SyntaxError::UnknownCharacter(ref span) => span.clone(),
+            SyntaxError::UnknownCharacter(ref span) |
+            SyntaxError::Invalid(_, ref span) => span.clone(),
         }
     }
 }
@@ -88,6 +91,7 @@ pub enum Token {
     CloseTag,
     Exit,
     Function,
+    Fn,
     Const,
     Return,
     Yield,
@@ -113,6 +117,7 @@ pub enum Token {
     As,
     Switch,
     EndSwitch,
+    Match,
     Case,
     Default,
     Break,
@@ -127,20 +132,30 @@ pub enum Token {
     Implements,
     /// T_OBJECT_OPERATOR
     ObjectOp,
+    /// T_NULLSAFE_OBJECT_OPERATOR
+    NullsafeObjectOp,
     /// T_PAAMAYIM_NEKUDOTAYIM
     ScopeOp,
     NsSeparator,
     Ellipsis,
+    /// T_ATTRIBUTE, the start of an attribute group `#[`
+    AttributeStart,
     Coalesce,
+    CoalesceEqual,
     New,
     Clone,
     Var,
     CastInt,
+    CastInteger,
     CastDouble,
+    CastFloat,
+    CastReal,
     CastString,
+    CastBinary,
     CastArray,
     CastObject,
     CastBool,
+    CastBoolean,
     CastUnset,
     Eval,
     Include,
@@ -203,6 +218,8 @@ pub enum Token {
     /// like 'test', constant encapsed string
     ConstantEncapsedString(RcStr),
     BinaryCharSequence(Rc<Vec<u8>>),
+    /// the raw data following __halt_compiler();
+    HaltCompilerData(Rc<Vec<u8>>),
     InlineHtml(RcStr),
     // magic-tokens
     MagicClass,
@@ -224,7 +241,7 @@ impl Token {
             | Token::Do | Token::While | Token::EndWhile
             | Token::For | /*Token::EndFor |*/ Token::Foreach | Token::EndForeach | Token::Declare | Token::EndDeclare | Token::As | Token::Try | Token::Catch | Token::Finally
             | Token::Throw | Token::Use | Token::Insteadof | Token::Global | Token::Var | Token::Unset | Token::Isset | Token::Empty | Token::Continue | Token::Goto
-            | Token::Function | Token::Const | Token::Return | Token::Print | Token::Yield | Token::List | Token::Switch | Token::EndSwitch | Token::Case | Token::Default
+            | Token::Function | Token::Fn | Token::Const | Token::Return | Token::Print | Token::Yield | Token::List | Token::Switch | Token::EndSwitch | Token::Match | Token::Case | Token::Default
             | Token::Break | Token::Array | Token::Callable | Token::Extends | Token::Implements | Token::Namespace | Token::Trait | Token::Interface | Token::Class
             | Token::MagicClass | Token::MagicTrait | Token::MagicFunction | Token::MagicMethod | Token::MagicLine | Token::MagicFile | Token::MagicDir
             | Token::MagicNamespace => true,
@@ -241,6 +258,7 @@ impl Token {
             Token::CloseTag => "?>",
             Token::Exit => "exit",
             Token::Function => "function",
+            Token::Fn => "fn",
             Token::Const => "const",
             Token::Return => "return",
             Token::Try => "try",
@@ -264,6 +282,7 @@ impl Token {
             Token::As => "as",
             Token::Switch => "switch",
             Token::EndSwitch => "endswitch",
+            Token::Match => "match",
             Token::Case => "case",
             Token::Default => "default",
             Token::Break => "break",
@@ -277,19 +296,27 @@ impl Token {
             Token::Extends => "extends",
             Token::Implements => "implements",
             Token::ObjectOp => "->",
+            Token::NullsafeObjectOp => "?->",
             Token::ScopeOp => "::",
             Token::NsSeparator => "\\",
             Token::Ellipsis => "...",
+            Token::AttributeStart => "#[",
             Token::Coalesce => "??",
+            Token::CoalesceEqual => "??=",
             Token::New => "new",
             Token::Clone => "clone",
             Token::Var => "var",
             Token::CastInt => "int",
+            Token::CastInteger => "integer",
             Token::CastDouble => "double",
+            Token::CastFloat => "float",
+            Token::CastReal => "real",
             Token::CastString => "string",
+            Token::CastBinary => "binary",
             Token::CastArray => "array",
             Token::CastObject => "object",
             Token::CastBool => "bool",
+            Token::CastBoolean => "boolean",
             Token::CastUnset => "unset",
             Token::Eval => "eval",
             Token::IncludeOnce => "include_once",